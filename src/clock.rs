@@ -13,10 +13,13 @@
 //! - [proc64()](fn.proc64.html) - Get the processor time in nanoseconds
 //! - [thread()](fn.thread.html) - Get the thread time in seconds
 //! - [thread64()](fn.thread64.html) - Get the thread time in nanoseconds
+//! - [Deadline](struct.Deadline.html) - A point in monotonic time shared by several operations
 //!
 //! See also:
 //! - [Lua reference: Module clock](https://www.tarantool.io/en/doc/latest/reference/reference_lua/clock/)
 //! - [C API reference: Module clock](https://www.tarantool.io/en/doc/latest/dev_guide/reference_capi/clock/)
+use std::time::Duration;
+
 use crate::ffi::tarantool as ffi;
 
 /// The wall clock time.
@@ -122,3 +125,42 @@ pub fn thread() -> f64 {
 pub fn thread64() -> u64 {
     unsafe { ffi::clock_thread64() }
 }
+
+/// An absolute point in [monotonic](fn.monotonic.html) time.
+///
+/// Unlike a timeout, a deadline can be shared by several consecutive operations, so all of them together fit into
+/// the same time budget.
+///
+/// Example:
+/// ```rust
+/// use std::time::Duration;
+/// use tarantool::clock::Deadline;
+///
+/// let deadline = Deadline::after(Duration::from_secs(1));
+/// assert!(!deadline.is_expired());
+/// assert!(deadline.remaining() <= Duration::from_secs(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Deadline(f64);
+
+impl Deadline {
+    /// Deadline `timeout` from now.
+    pub fn after(timeout: Duration) -> Self {
+        Deadline(monotonic() + timeout.as_secs_f64())
+    }
+
+    /// Time left until the deadline (zero if the deadline has passed).
+    pub fn remaining(&self) -> Duration {
+        let remaining = self.0 - monotonic();
+        if remaining > 0. {
+            Duration::from_secs_f64(remaining)
+        } else {
+            Duration::from_secs(0)
+        }
+    }
+
+    /// Returns `true` if the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        monotonic() >= self.0
+    }
+}
@@ -5,7 +5,7 @@
 use std::convert::TryFrom;
 use std::ffi::c_void;
 use std::io;
-use std::io::{Read, Write};
+use std::io::{IoSlice, IoSliceMut, Read, Write};
 use std::mem::forget;
 use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
 use std::os::raw::c_char;
@@ -14,6 +14,7 @@ use std::time::Duration;
 
 use failure::_core::ptr::null_mut;
 
+use crate::clock::Deadline;
 use crate::error::{Error, TarantoolError};
 use crate::ffi::tarantool as ffi;
 use crate::fiber::unpack_callback;
@@ -62,6 +63,19 @@ impl CoIOStream {
         })
     }
 
+    /// Opens a TCP connection to a remote host, giving up at `deadline`.
+    ///
+    /// Fails with `ETIMEDOUT` right away if the deadline has already passed.
+    pub fn connect_deadline(
+        addr: &SocketAddr,
+        deadline: Deadline,
+    ) -> Result<CoIOStream, io::Error> {
+        if deadline.is_expired() {
+            return Err(io::Error::from_raw_os_error(libc::ETIMEDOUT));
+        }
+        Self::connect_timeout(addr, deadline.remaining())
+    }
+
     /// Pull some bytes from this source into the specified buffer. Returns how many bytes were read or 0 on timeout.
     pub fn read_with_timeout(
         &mut self,
@@ -71,6 +85,34 @@ impl CoIOStream {
         read(self.fd, buf, timeout)
     }
 
+    /// Read the exact number of bytes required to fill `buf`, giving up at `deadline`.
+    ///
+    /// Unlike [read_with_timeout()](#method.read_with_timeout) called in a loop, the total wait is limited: each wait
+    /// for the next portion of data gets only the time left until `deadline`. Fails with `ETIMEDOUT` if the deadline
+    /// passes before `buf` is filled (the data read so far is lost) and with `UnexpectedEof` if the peer closes the
+    /// connection.
+    pub fn read_until_deadline(
+        &mut self,
+        buf: &mut [u8],
+        deadline: Deadline,
+    ) -> Result<(), io::Error> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            if deadline.is_expired() {
+                return Err(io::Error::from_raw_os_error(libc::ETIMEDOUT));
+            }
+            match read(self.fd, &mut buf[filled..], Some(deadline.remaining())) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(len) => filled += len,
+                Err(e)
+                    if e.kind() == io::ErrorKind::Interrupted
+                        || e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
     /// Write a buffer into this writer. Returning how many bytes were written or 0 on timeout.
     pub fn write_with_timeout(
         &mut self,
@@ -79,6 +121,38 @@ impl CoIOStream {
     ) -> Result<usize, io::Error> {
         write(self.fd, buf, timeout)
     }
+
+    /// Write all buffers into this writer, handling partial writes. Yields until all data is written.
+    ///
+    /// Uses `writev(2)`, so the buffers are not copied into one contiguous buffer.
+    pub fn write_all_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<(), io::Error> {
+        let total_len: usize = bufs.iter().map(|buf| buf.len()).sum();
+        let mut written = 0;
+        while written < total_len {
+            let slices = skip_bytes(bufs, written);
+            match write_vectored(self.fd, &slices, None) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(len) => written += len,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the slices left after skipping first `skip` bytes of `bufs`.
+fn skip_bytes<'a>(bufs: &'a [IoSlice<'_>], mut skip: usize) -> Vec<IoSlice<'a>> {
+    let mut result = Vec::with_capacity(bufs.len());
+    for buf in bufs {
+        if skip >= buf.len() {
+            skip -= buf.len();
+        } else {
+            result.push(IoSlice::new(&buf[skip..]));
+            skip = 0;
+        }
+    }
+    result
 }
 
 impl IntoRawFd for CoIOStream {
@@ -99,6 +173,10 @@ impl Read for CoIOStream {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
         self.read_with_timeout(buf, None)
     }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize, io::Error> {
+        read_vectored(self.fd, bufs, None)
+    }
 }
 
 impl Write for CoIOStream {
@@ -106,6 +184,10 @@ impl Write for CoIOStream {
         self.write_with_timeout(buf, None)
     }
 
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize, io::Error> {
+        write_vectored(self.fd, bufs, None)
+    }
+
     fn flush(&mut self) -> Result<(), io::Error> {
         Ok(())
     }
@@ -164,9 +246,11 @@ impl TryFrom<TcpListener> for CoIOListener {
 /// - `fd` - non-blocking socket file description
 /// - `events` - requested events to wait. Combination of [CoIOFlags::READ | CoIOFlags::WRITE](struct.CoIOFlags.html) bit flags.
 /// - `timeoout` - timeout in seconds.
+///
+/// Returns an error with `errno = ETIMEDOUT` on timeout.
 pub fn coio_wait(fd: RawFd, flags: ffi::CoIOFlags, timeout: f64) -> Result<(), io::Error> {
     match unsafe { ffi::coio_wait(fd, flags.bits(), timeout) } {
-        0 => Err(io::ErrorKind::TimedOut.into()),
+        0 => Err(io::Error::from_raw_os_error(libc::ETIMEDOUT)),
         _ => Ok(()),
     }
 }
@@ -201,6 +285,39 @@ where
     unsafe { ffi::coio_call(trampoline, callback_ptr, Box::into_raw(Box::<T>::new(arg))) }
 }
 
+/// Run a blocking closure in the eio thread pool. Yield and wait until it is complete.
+///
+/// Use it to perform blocking operations (e.g. file system I/O) without stalling the event loop.
+/// The closure is executed in a separate thread, so it must not call any Tarantool API.
+/// Its return value is moved back to the calling fiber.
+///
+/// Returns an error only if the task could not be created (`errno = ENOMEM`).
+///
+/// ```no_run
+/// use tarantool::coio;
+///
+/// let data = coio::call(|| std::fs::read("/etc/hostname")).unwrap();
+/// ```
+pub fn call<F, R>(f: F) -> Result<R, Error>
+where
+    F: FnOnce() -> R + Send,
+    R: Send,
+{
+    let mut f = Some(f);
+    let mut result = None;
+    let res = coio_call(
+        &mut |_: Box<()>| {
+            result = f.take().map(|f| f());
+            0
+        },
+        (),
+    );
+    match result {
+        Some(result) if res == 0 => Ok(result),
+        _ => Err(io::Error::last_os_error().into()),
+    }
+}
+
 /// Fiber-friendly version of `getaddrinfo(3)`.
 ///
 /// - `host` - host name, i.e. "tarantool.org"
@@ -286,3 +403,69 @@ pub(crate) fn write(fd: RawFd, buf: &[u8], timeout: Option<Duration>) -> Result<
         Ok(result as usize)
     }
 }
+
+#[inline(always)]
+pub(crate) fn read_vectored(
+    fd: RawFd,
+    bufs: &mut [IoSliceMut<'_>],
+    timeout: Option<Duration>,
+) -> Result<usize, io::Error> {
+    // `IoSliceMut` is guaranteed to be ABI compatible with `iovec`
+    let iov = bufs.as_ptr() as *const libc::iovec;
+    let iov_count = bufs.len().min(libc::c_int::MAX as usize) as libc::c_int;
+    let result = unsafe { libc::readv(fd, iov, iov_count) };
+    if result >= 0 {
+        return Ok(result as usize);
+    }
+
+    let err = io::Error::last_os_error();
+    if err.kind() != io::ErrorKind::WouldBlock {
+        return Err(err);
+    }
+
+    let timeout = match timeout {
+        None => TIMEOUT_INFINITY,
+        Some(timeout) => timeout.as_secs_f64(),
+    };
+
+    coio_wait(fd, ffi::CoIOFlags::READ, timeout)?;
+    let result = unsafe { libc::readv(fd, iov, iov_count) };
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result as usize)
+    }
+}
+
+#[inline(always)]
+pub(crate) fn write_vectored(
+    fd: RawFd,
+    bufs: &[IoSlice<'_>],
+    timeout: Option<Duration>,
+) -> Result<usize, io::Error> {
+    // `IoSlice` is guaranteed to be ABI compatible with `iovec`
+    let iov = bufs.as_ptr() as *const libc::iovec;
+    let iov_count = bufs.len().min(libc::c_int::MAX as usize) as libc::c_int;
+    let result = unsafe { libc::writev(fd, iov, iov_count) };
+    if result >= 0 {
+        return Ok(result as usize);
+    }
+
+    let err = io::Error::last_os_error();
+    if err.kind() != io::ErrorKind::WouldBlock {
+        return Err(err);
+    }
+
+    let timeout = match timeout {
+        None => TIMEOUT_INFINITY,
+        Some(timeout) => timeout.as_secs_f64(),
+    };
+
+    coio_wait(fd, ffi::CoIOFlags::WRITE, timeout)?;
+    let result = unsafe { libc::writev(fd, iov, iov_count) };
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result as usize)
+    }
+}
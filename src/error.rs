@@ -27,6 +27,9 @@ use rmp::encode::ValueWriteError;
 
 use crate::ffi::tarantool as ffi;
 
+/// A specialized `Result` type for the crate: `Result<T>` is `Result<T, tarantool::error::Error>`.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
 /// Represents all error cases for all routines of crate (including Tarantool errors)
 #[derive(Debug, Fail)]
 pub enum Error {
@@ -36,6 +39,11 @@ pub enum Error {
     #[fail(display = "IO error: {}", _0)]
     IO(io::Error),
 
+    /// IO error caused by a failed system call (e.g. in [coio](../coio/index.html)): `errno` is what the call has set
+    /// (`ECONNRESET`, `ETIMEDOUT` etc.), `kind` is its portable counterpart.
+    #[fail(display = "IO error: {:?} (errno {})", kind, errno)]
+    Io { errno: i32, kind: io::ErrorKind },
+
     #[cfg(feature = "raft_node")]
     #[fail(display = "Raft: {}", _0)]
     Raft(raft::Error),
@@ -46,6 +54,9 @@ pub enum Error {
     #[fail(display = "Failed to decode tuple: {}", _0)]
     Decode(rmp_serde::decode::Error),
 
+    #[fail(display = "Failed to decode tuple: {}", _0)]
+    DecodeField(FieldDecodeError),
+
     #[cfg(feature = "raft_node")]
     #[fail(display = "Protobuf encode/decode error: {}", _0)]
     Protobuf(protobuf::ProtobufError),
@@ -62,6 +73,21 @@ pub enum Error {
     #[fail(display = "Value write error: {}", _0)]
     ValueWrite(ValueWriteError),
 
+    #[fail(display = "Tuple data must be a MessagePack array")]
+    NotAnArray,
+
+    #[fail(
+        display = "{:?} index doesn't support {:?} iterator",
+        index_type, iterator
+    )]
+    UnsupportedIterator {
+        index_type: crate::index::IndexType,
+        iterator: crate::index::IteratorType,
+    },
+
+    #[fail(display = "Select returned more than {} rows", max_rows)]
+    TooManyRows { max_rows: usize },
+
     #[fail(display = "Transaction issue: {}", _0)]
     Transaction(TransactionError),
 
@@ -70,9 +96,64 @@ pub enum Error {
     Remote(crate::net_box::ResponseError),
 }
 
+impl Error {
+    /// Returns `errno` of the failed system call if this is an IO error caused by an OS error
+    /// (e.g. `ECONNRESET`, `ETIMEDOUT`).
+    pub fn errno(&self) -> Option<i32> {
+        match self {
+            Error::IO(error) => error.raw_os_error(),
+            Error::Io { errno, .. } => Some(*errno),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of IO error (`None` if this is not an IO error).
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        match self {
+            Error::IO(error) => Some(error.kind()),
+            Error::Io { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Converts IO error (either `IO` or `Io`) back into `io::Error`, returns other errors as is.
+    pub fn into_io_error(self) -> Result<io::Error, Error> {
+        match self {
+            Error::IO(error) => Ok(error),
+            Error::Io { errno, .. } => Ok(io::Error::from_raw_os_error(errno)),
+            error => Err(error),
+        }
+    }
+
+    /// Returns the underlying [TarantoolError](struct.TarantoolError.html) if this is an error raised by Tarantool.
+    pub fn as_tarantool(&self) -> Option<&TarantoolError> {
+        match self {
+            Error::Tarantool(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Returns the error code if this is an error raised by Tarantool, e.g. `SpaceExists` when a space can't be
+    /// created because of a name clash.
+    pub fn tarantool_code(&self) -> Option<TarantoolErrorCode> {
+        match self {
+            Error::Tarantool(error) => Some(error.error_code()),
+            _ => None,
+        }
+    }
+}
+
+/// OS errors become [Error::Io](enum.Error.html#variant.Io), so their `errno` can be matched on, other errors
+/// become `Error::IO`.
 impl From<io::Error> for Error {
     fn from(error: io::Error) -> Self {
-        Error::IO(error)
+        match error.raw_os_error() {
+            Some(errno) => Error::Io {
+                errno,
+                kind: error.kind(),
+            },
+            None => Error::IO(error),
+        }
     }
 }
 
@@ -139,6 +220,46 @@ impl From<crate::net_box::ResponseError> for Error {
     }
 }
 
+impl From<FieldDecodeError> for Error {
+    fn from(error: FieldDecodeError) -> Self {
+        Error::DecodeField(error)
+    }
+}
+
+/// Tuple field decode error: tells which field of the tuple could not be deserialized
+#[derive(Debug)]
+pub struct FieldDecodeError {
+    pub(crate) fieldno: u32,
+    pub(crate) name: Option<&'static str>,
+    pub(crate) error: rmp_serde::decode::Error,
+}
+
+impl FieldDecodeError {
+    /// Return one-based number of the field (as in Tarantool's own error messages)
+    pub fn fieldno(&self) -> u32 {
+        self.fieldno
+    }
+
+    /// Return name of the corresponding structure member (if deserialized into structure)
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Return underlying MsgPack decode error (contains expected and actual value types)
+    pub fn error(&self) -> &rmp_serde::decode::Error {
+        &self.error
+    }
+}
+
+impl Display for FieldDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "field {} ({}): {}", self.fieldno, name, self.error),
+            None => write!(f, "field {}: {}", self.fieldno, self.error),
+        }
+    }
+}
+
 /// Transaction-related error cases
 #[derive(Debug, Fail)]
 pub enum TransactionError {
@@ -150,6 +271,9 @@ pub enum TransactionError {
 
     #[fail(display = "Failed to rollback")]
     FailedToRollback,
+
+    #[fail(display = "Transaction the iterator was opened in has ended")]
+    Ended,
 }
 
 impl From<TransactionError> for Error {
@@ -430,6 +554,43 @@ pub enum TarantoolErrorCode {
     FuncIndexFormat = 199,
     FuncIndexParts = 200,
     BootstrapReadonly = 201,
+    SqlFuncWrongRetCount = 202,
+    FuncInvalidReturnType = 203,
+    SqlParserGenericWithPos = 204,
+    ReplicaNotAnon = 205,
+    CannotRegister = 206,
+    SessionSettingInvalidValue = 207,
+    SqlPrepare = 208,
+    WrongQueryId = 209,
+    SequenceNotStarted = 210,
+    NoSuchSessionSetting = 211,
+    UncommittedForeignSyncTxns = 212,
+    SyncMasterMismatch = 213,
+    SyncQuorumTimeout = 214,
+    SyncRollback = 215,
+    TupleMetadataIsTooBig = 216,
+    XlogGap = 217,
+    TooEarlySubscribe = 218,
+    SqlCantAddAutoinc = 219,
+    QuorumWait = 220,
+    InterferingPromote = 221,
+    ElectionDisabled = 222,
+    TxnRollback = 223,
+    NotLeader = 224,
+    SyncQueueUnclaimed = 225,
+    SyncQueueForeign = 226,
+    UnableToProcessInStream = 227,
+    UnableToProcessOutOfStream = 228,
+    TransactionTimeout = 229,
+    ActiveTimer = 230,
+    TupleFieldCountLimit = 231,
+    CreateConstraint = 232,
+    FieldConstraintFailed = 233,
+    TupleConstraintFailed = 234,
+    CreateForeignKey = 235,
+    ForeignKeyIntegrity = 236,
+    FieldForeignKeyFailed = 237,
+    ComplexForeignKeyFailed = 238,
 }
 
 /// Clear the last error.
@@ -449,3 +610,47 @@ macro_rules! set_error {
         }
     }};
 }
+
+/// Set the last error and return it from the current function.
+///
+/// The error is converted into the function's error type with `From`, so the function may return
+/// [Result](type.Result.html) or anything else constructible from [Error](enum.Error.html).
+///
+/// Example:
+/// ```rust
+/// use tarantool::bail;
+/// use tarantool::error::{Result, TarantoolErrorCode};
+///
+/// fn get_user(name: &str) -> Result<u32> {
+///     bail!(TarantoolErrorCode::NoSuchUser, "User '{}' is not found", name)
+/// }
+/// ```
+#[macro_export]
+macro_rules! bail {
+    ($code:expr, $($msg_args:expr),+) => {{
+        $crate::set_error!($code, $($msg_args),+);
+        return Err($crate::error::Error::from($crate::error::TarantoolError::last()).into());
+    }};
+}
+
+/// Set the last error and return it from the current function (see [bail!](macro.bail.html)) unless `cond` is
+/// `true`.
+///
+/// Example:
+/// ```rust
+/// use tarantool::ensure;
+/// use tarantool::error::{Result, TarantoolErrorCode};
+///
+/// fn set_quota(quota: i64) -> Result<()> {
+///     ensure!(quota >= 0, TarantoolErrorCode::IllegalParams, "Negative quota: {}", quota);
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr, $code:expr, $($msg_args:expr),+) => {
+        if !$cond {
+            $crate::bail!($code, $($msg_args),+);
+        }
+    };
+}
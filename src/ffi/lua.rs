@@ -1,15 +1,20 @@
 #![allow(non_camel_case_types)]
-use std::os::raw::{c_double, c_int, c_schar};
+use std::os::raw::{c_double, c_int, c_schar, c_void};
 use std::ptr::{null, null_mut};
 
+use crate::ffi::tarantool::BoxTuple;
+
 /// Module provides FFI bindings for the following constants,
 /// types and functions, realted to Lua C API:
 /// 1. Plain lua C API
 /// 2. lauxlib
 /// 3. Lua utitlites, implemented in Tarantool
 
+pub const LUA_REGISTRYINDEX: c_int = -10000;
 pub const LUA_GLOBALSINDEX: c_int = -10002;
 
+pub const LUA_TNIL: c_int = 0;
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct lua_State {
@@ -44,23 +49,30 @@ extern "C" {
     pub fn lua_pushnumber(l: *mut lua_State, n: c_double);
     pub fn lua_pushcclosure(l: *mut lua_State, fun: lua_CFunction, n: c_int);
     pub fn lua_pushnil(l: *mut lua_State);
+    pub fn lua_pushlightuserdata(l: *mut lua_State, p: *mut c_void);
     pub fn lua_pushvalue(l: *mut lua_State, idx: c_int);
     pub fn lua_tointeger(l: *mut lua_State, idx: c_int) -> isize;
     pub fn lua_tolstring(l: *mut lua_State, idx: c_int, len: *mut usize) -> *const c_schar;
+    pub fn lua_touserdata(l: *mut lua_State, idx: c_int) -> *mut c_void;
+    pub fn lua_type(l: *mut lua_State, idx: c_int) -> c_int;
     pub fn lua_setfield(l: *mut lua_State, idx: c_int, s: *const c_schar);
     pub fn lua_getfield(l: *mut lua_State, idx: c_int, s: *const c_schar);
     pub fn lua_createtable(l: *mut lua_State, narr: c_int, nrec: c_int);
     pub fn lua_gettable(l: *mut lua_State, idx: c_int);
     pub fn lua_settable(l: *mut lua_State, idx: c_int);
+    pub fn lua_rawgeti(l: *mut lua_State, idx: c_int, n: c_int);
     pub fn lua_remove(l: *mut lua_State, idx: c_int);
 
     // lauxlib functions.
     pub fn luaL_register(l: *mut lua_State, libname: *const c_schar, lr: *const luaL_Reg);
     pub fn luaL_error(l: *mut lua_State, fmt: *const c_schar, ...) -> c_int;
+    pub fn luaL_ref(l: *mut lua_State, t: c_int) -> c_int;
+    pub fn luaL_unref(l: *mut lua_State, t: c_int, r: c_int);
 
     // Lua Tarantool util functios.
     pub fn luaT_state() -> *mut lua_State;
     pub fn luaT_call(l: *mut lua_State, nargs: c_int, nreturns: c_int) -> isize;
+    pub fn luaT_istuple(l: *mut lua_State, idx: c_int) -> *mut BoxTuple;
 }
 
 #[inline(always)]
@@ -73,6 +85,11 @@ pub unsafe fn lua_pushcfunction(state: *mut lua_State, f: lua_CFunction) {
     lua_pushcclosure(state, f, 0);
 }
 
+#[inline(always)]
+pub fn lua_upvalueindex(i: c_int) -> c_int {
+    LUA_GLOBALSINDEX - i
+}
+
 #[inline(always)]
 pub unsafe fn lua_tostring(state: *mut lua_State, i: c_int) -> *const c_schar {
     lua_tolstring(state, i, null_mut())
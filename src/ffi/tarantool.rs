@@ -70,6 +70,7 @@ extern "C" {
         fiber_attr: *const FiberAttr,
         f: FiberFunc,
     ) -> *mut Fiber;
+    pub fn fiber_self() -> *mut Fiber;
     pub fn fiber_yield();
     pub fn fiber_start(callee: *mut Fiber, ...);
     pub fn fiber_wakeup(f: *mut Fiber);
@@ -158,6 +159,7 @@ extern "C" {
 // Transaction.
 extern "C" {
     pub fn box_txn() -> bool;
+    pub fn box_txn_id() -> i64;
     pub fn box_txn_begin() -> c_int;
     pub fn box_txn_commit() -> c_int;
     pub fn box_txn_rollback() -> c_int;
@@ -299,7 +301,14 @@ extern "C" {
     pub fn box_tuple_to_buf(tuple: *const BoxTuple, buf: *mut c_char, size: usize) -> isize;
     pub fn box_tuple_format_default() -> *mut BoxTupleFormat;
     pub fn box_tuple_format(tuple: *const BoxTuple) -> *mut BoxTupleFormat;
+    pub fn box_tuple_format_ref(format: *mut BoxTupleFormat);
+    pub fn box_tuple_format_unref(format: *mut BoxTupleFormat);
     pub fn box_tuple_field(tuple: *const BoxTuple, fieldno: u32) -> *const c_char;
+    pub fn box_tuple_update(
+        tuple: *const BoxTuple,
+        expr: *const c_char,
+        expr_end: *const c_char,
+    ) -> *mut BoxTuple;
     pub fn box_tuple_compare(
         tuple_a: *mut BoxTuple,
         tuple_b: *mut BoxTuple,
@@ -3,7 +3,13 @@
 //! With the fiber module, you can:
 //! - create, run and manage [fibers](struct.Fiber.html),
 //! - use a synchronization mechanism for fibers, similar to “condition variables” and similar to operating-system
-//! functions such as `pthread_cond_wait()` plus `pthread_cond_signal()`.
+//! functions such as `pthread_cond_wait()` plus `pthread_cond_signal()`,
+//! - pass values between fibers using [channels](channel/index.html),
+//! - protect shared data with [mutexes and condition variables](mutex/index.html),
+//! - limit concurrency with [semaphores](semaphore/index.html),
+//! - run one-time initialization with [Once](once/index.html),
+//! - stop worker fibers gracefully with a [shutdown barrier](shutdown/index.html),
+//! - attach [context values](context/index.html) (e.g. trace ids) to fibers.
 //!
 //! See also:
 //! - [Threads, fibers and yields](https://www.tarantool.io/en/doc/latest/book/box/atomic/#threads-fibers-and-yields)
@@ -12,13 +18,31 @@
 use std::ffi::CString;
 use std::marker::PhantomData;
 use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
 use std::time::Duration;
 
 use va_list::VaList;
 
-use crate::error::{Error, TarantoolError};
+use crate::error::{Error, TarantoolError, TarantoolErrorCode};
+use crate::ffi::helper::new_c_str;
+use crate::ffi::lua;
 use crate::ffi::tarantool as ffi;
 
+pub mod channel;
+pub mod context;
+pub mod mutex;
+pub mod once;
+pub mod semaphore;
+pub mod shutdown;
+
+pub use channel::PrioritySelect;
+pub use context::{context, set_context, spawn_with_context, ContextGuard};
+pub use mutex::{CondVar, Mutex, MutexGuard};
+pub use once::Once;
+pub use semaphore::{Semaphore, SemaphorePermit};
+pub use shutdown::ShutdownBarrier;
+
 /// A fiber is a set of instructions which are executed with cooperative multitasking.
 ///
 /// Fibers managed by the fiber module are associated with a user-supplied function called the fiber function.
@@ -56,6 +80,7 @@ use crate::ffi::tarantool as ffi;
 /// ```
 pub struct Fiber<'a, T: 'a> {
     inner: *mut ffi::Fiber,
+    name: String,
     callback: *mut c_void,
     phantom: PhantomData<&'a T>,
 }
@@ -80,9 +105,10 @@ impl<'a, T> Fiber<'a, T> {
     where
         F: FnMut(Box<T>) -> i32,
     {
-        let (callback_ptr, trampoline) = unsafe { unpack_callback(callback) };
+        let (callback_ptr, trampoline) = unsafe { unpack_fiber_callback(callback) };
         Self {
             inner: unsafe { ffi::fiber_new(CString::new(name).unwrap().into_raw(), trampoline) },
+            name: name.to_string(),
             callback: callback_ptr,
             phantom: PhantomData,
         }
@@ -104,7 +130,7 @@ impl<'a, T> Fiber<'a, T> {
     where
         F: FnMut(Box<T>) -> i32,
     {
-        let (callback_ptr, trampoline) = unsafe { unpack_callback(callback) };
+        let (callback_ptr, trampoline) = unsafe { unpack_fiber_callback(callback) };
         Self {
             inner: unsafe {
                 ffi::fiber_new_ex(
@@ -113,6 +139,7 @@ impl<'a, T> Fiber<'a, T> {
                     trampoline,
                 )
             },
+            name: name.to_string(),
             callback: callback_ptr,
             phantom: PhantomData,
         }
@@ -129,6 +156,11 @@ impl<'a, T> Fiber<'a, T> {
         }
     }
 
+    /// Returns the name the fiber was created with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Interrupt a synchronous wait of a fiber.
     pub fn wakeup(&self) {
         unsafe { ffi::fiber_wakeup(self.inner) }
@@ -146,9 +178,16 @@ impl<'a, T> Fiber<'a, T> {
     ///
     /// The fiber must not be detached (See also: [fiber.set_joinable()](#method.set_joinable)).
     ///
-    /// Return: fiber function return code
-    pub fn join(&self) -> i32 {
-        unsafe { ffi::fiber_join(self.inner) }
+    /// Returns:
+    /// - `Ok(code)` - fiber function return code
+    /// - `Err(...)` - error set by the fiber function which returned non-zero code (e.g. if the fiber panicked, the
+    ///   error contains the panic message)
+    pub fn join(&self) -> Result<i32, Error> {
+        let result = unsafe { ffi::fiber_join(self.inner) };
+        if result != 0 {
+            TarantoolError::maybe_last()?;
+        }
+        Ok(result)
     }
 
     /// Set fiber to be joinable (false by default).
@@ -172,6 +211,85 @@ impl<'a, T> Fiber<'a, T> {
     }
 }
 
+/// Fiber factory which can be used to configure the properties of the new fiber.
+///
+/// Example:
+/// ```rust
+/// use tarantool::fiber::Builder;
+/// let mut fiber = Builder::new()
+///     .name("worker")
+///     .build(&mut |_| {
+///         println!("I'm a fiber");
+///         0
+///     });
+/// fiber.start(());
+/// ```
+pub struct Builder {
+    name: Option<String>,
+    attr: Option<FiberAttr>,
+}
+
+impl Builder {
+    /// Default name of fibers created without [name()](#method.name).
+    pub const DEFAULT_NAME: &'static str = "rust-fiber";
+
+    /// Creates a new fiber builder.
+    pub fn new() -> Self {
+        Builder {
+            name: None,
+            attr: None,
+        }
+    }
+
+    /// Names the fiber-to-be. The name is visible in `fiber.info()` and `box.info.fibers`.
+    ///
+    /// If not set, [DEFAULT_NAME](#associatedconstant.DEFAULT_NAME) is used.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the stack size for the new fiber.
+    pub fn stack_size(mut self, stack_size: usize) -> Result<Self, Error> {
+        let mut attr = self.attr.take().unwrap_or_else(FiberAttr::new);
+        attr.set_stack_size(stack_size)?;
+        self.attr = Some(attr);
+        Ok(self)
+    }
+
+    /// Returns the stack size the new fiber will get: either the one set with
+    /// [stack_size()](#method.stack_size) or [default_stack_size()](fn.default_stack_size.html).
+    pub fn get_stack_size(&self) -> usize {
+        match &self.attr {
+            None => default_stack_size(),
+            Some(attr) => attr.stack_size(),
+        }
+    }
+
+    /// Creates a fiber with the configured properties (see [Fiber::new()](struct.Fiber.html#method.new)).
+    pub fn build<'a, T, F>(self, callback: &mut F) -> Fiber<'a, T>
+    where
+        F: FnMut(Box<T>) -> i32,
+    {
+        let name = self.name.as_deref().unwrap_or(Self::DEFAULT_NAME);
+        match &self.attr {
+            None => Fiber::new(name, callback),
+            Some(attr) => Fiber::new_with_attr(name, attr, callback),
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the stack size of fibers created without custom [FiberAttr](struct.FiberAttr.html).
+pub fn default_stack_size() -> usize {
+    unsafe { ffi::fiber_attr_getstacksize(ptr::null_mut()) }
+}
+
 /// Make it possible or not possible to wakeup the current
 /// fiber immediately when it's cancelled.
 ///
@@ -233,6 +351,43 @@ pub fn reschedule() {
     unsafe { ffi::fiber_reschedule() }
 }
 
+/// Returns how many times the current fiber has yielded (context switches, `fiber.self():csw()`).
+///
+/// Comparing the values taken before and after a piece of code shows whether it yields at all, which helps to find
+/// loops starving other fibers. Only the current fiber is queried, but still via Lua API, so this function is meant
+/// for debugging rather than for hot paths. Requires Tarantool 2.10 or newer.
+pub fn yields() -> u64 {
+    unsafe {
+        let state = lua::luaT_state();
+        let top = lua::lua_gettop(state);
+        let l = lua::lua_newthread(state);
+
+        // -- fiber = package.loaded.fiber
+        lua::lua_getglobal(l, new_c_str("package").as_ptr());
+        lua::lua_getfield(l, -1, new_c_str("loaded").as_ptr());
+        lua::lua_getfield(l, -1, new_c_str("fiber").as_ptr());
+
+        // -- f = fiber.self()
+        lua::lua_getfield(l, -1, new_c_str("self").as_ptr());
+        if lua::luaT_call(l, 0, 1) != 0 {
+            lua::lua_settop(state, top);
+            panic!("fiber.self() failed: {}", TarantoolError::last());
+        }
+
+        // -- f:csw()
+        lua::lua_getfield(l, -1, new_c_str("csw").as_ptr());
+        lua::lua_pushvalue(l, -2);
+        if lua::luaT_call(l, 1, 1) != 0 {
+            lua::lua_settop(state, top);
+            panic!("fiber:csw() failed: {}", TarantoolError::last());
+        }
+        let csw = lua::lua_tointeger(l, -1);
+
+        lua::lua_settop(state, top);
+        csw as u64
+    }
+}
+
 /// Fiber attributes container
 pub struct FiberAttr {
     inner: *mut ffi::FiberAttr,
@@ -320,12 +475,18 @@ impl Cond {
 
     /// Wake one fiber waiting for the cond.
     /// Does nothing if no one is waiting. Does not yield.
+    ///
+    /// Waiters are queued in the order they called [wait()](#method.wait), so the fiber which has been waiting the
+    /// longest is woken first. The woken fiber runs once the current fiber yields.
     pub fn signal(&self) {
         unsafe { ffi::fiber_cond_signal(self.inner) }
     }
 
     /// Wake up all fibers waiting for the cond.
     /// Does not yield.
+    ///
+    /// Only the fibers waiting at the moment of the call are woken. They are scheduled in the order they started
+    /// waiting, the same as for [signal()](#method.signal).
     pub fn broadcast(&self) {
         unsafe { ffi::fiber_cond_broadcast(self.inner) }
     }
@@ -413,6 +574,36 @@ impl Drop for LatchGuard {
     }
 }
 
+/// Same as [unpack_callback()](fn.unpack_callback.html), but the trampoline catches panics: a panic is converted into
+/// a box error and the fiber function returns `-1` instead of unwinding into C code.
+unsafe fn unpack_fiber_callback<F, T>(callback: &mut F) -> (*mut c_void, ffi::FiberFunc)
+where
+    F: FnMut(Box<T>) -> i32,
+{
+    unsafe extern "C" fn trampoline<F, T>(mut args: VaList) -> i32
+    where
+        F: FnMut(Box<T>) -> i32,
+    {
+        let closure: &mut F = &mut *(args.get::<*const c_void>() as *mut F);
+        let arg = Box::from_raw(args.get::<*const c_void>() as *mut T);
+        match panic::catch_unwind(AssertUnwindSafe(|| (*closure)(arg))) {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = if let Some(message) = payload.downcast_ref::<&str>() {
+                    message
+                } else if let Some(message) = payload.downcast_ref::<String>() {
+                    message.as_str()
+                } else {
+                    "Box<Any>"
+                };
+                set_error!(TarantoolErrorCode::ProcC, "fiber panicked: {}", message);
+                -1
+            }
+        }
+    }
+    (callback as *mut F as *mut c_void, Some(trampoline::<F, T>))
+}
+
 pub(crate) unsafe fn unpack_callback<F, T>(callback: &mut F) -> (*mut c_void, ffi::FiberFunc)
 where
     F: FnMut(Box<T>) -> i32,
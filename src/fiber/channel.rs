@@ -0,0 +1,716 @@
+//! Channels for communication between fibers
+//!
+//! A channel is a bounded FIFO queue: [send()](struct.Channel.html#method.send) yields while the channel is full,
+//! [recv()](struct.Channel.html#method.recv) yields while the channel is empty.
+//!
+//! Example:
+//! ```rust
+//! use tarantool::fiber::channel::Channel;
+//!
+//! let channel = Channel::new(10);
+//! channel.send(1).unwrap();
+//! channel.close();
+//!
+//! assert_eq!(channel.recv(), Ok(1));
+//! assert!(channel.recv().is_err());
+//! ```
+//!
+//! See also:
+//! - [Lua reference: Channels](https://www.tarantool.io/en/doc/latest/reference/reference_lua/fiber_ipc/)
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::fiber::{clock, Cond};
+
+/// A channel for passing values of type `T` between fibers.
+///
+/// Cloning a channel returns a new handle to the same channel.
+pub struct Channel<T> {
+    inner: Rc<ChannelInner<T>>,
+}
+
+struct ChannelInner<T> {
+    buffer: RefCell<VecDeque<T>>,
+    capacity: usize,
+    is_closed: Cell<bool>,
+    senders: Cell<usize>,
+    readers: Cond,
+    writers: Cond,
+    selectors: RefCell<Vec<Rc<Cond>>>,
+    sends_blocked: Cell<u64>,
+    recvs_blocked: Cell<u64>,
+}
+
+impl<T> ChannelInner<T> {
+    fn notify_selectors(&self) {
+        for cond in self.selectors.borrow().iter() {
+            cond.signal();
+        }
+    }
+}
+
+impl<T> Channel<T> {
+    /// Create a new channel.
+    ///
+    /// - `capacity` - maximum number of values the channel can hold (must be greater than zero)
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "channel capacity must be greater than zero");
+        Channel {
+            inner: Rc::new(ChannelInner {
+                buffer: RefCell::new(VecDeque::with_capacity(capacity)),
+                capacity,
+                is_closed: Cell::new(false),
+                senders: Cell::new(0),
+                readers: Cond::new(),
+                writers: Cond::new(),
+                selectors: RefCell::new(Vec::new()),
+                sends_blocked: Cell::new(0),
+                recvs_blocked: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Send a value into the channel. Yields while the channel is full.
+    ///
+    /// Returns the value back inside [SendError](struct.SendError.html) if the channel is closed.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.send_deadline(value, None).map_err(|e| match e {
+            SendTimeoutError::Timeout(value) | SendTimeoutError::Closed(value) => SendError(value),
+        })
+    }
+
+    /// Same as [send()](#method.send), but gives up after `timeout`.
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        self.send_deadline(value, Some(clock() + timeout.as_secs_f64()))
+    }
+
+    /// Receive a value from the channel. Yields while the channel is empty.
+    ///
+    /// Values sent before the channel was closed are still delivered. Returns [RecvError](struct.RecvError.html)
+    /// if the channel is closed and empty.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.recv_deadline(None).map_err(|_| RecvError)
+    }
+
+    /// Same as [recv()](#method.recv), but gives up after `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.recv_deadline(Some(clock() + timeout.as_secs_f64()))
+    }
+
+    /// Send values produced by `iter` one by one, giving up after `timeout` for the whole batch.
+    ///
+    /// Returns the number of values sent. If the channel is closed or the timeout expires, returns
+    /// [SendIterError](struct.SendIterError.html) holding the number of values sent so far, the value which was not
+    /// sent and the rest of the iterator.
+    pub fn send_iter<I>(
+        &self,
+        iter: I,
+        timeout: Duration,
+    ) -> Result<usize, SendIterError<T, I::IntoIter>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let deadline = Some(clock() + timeout.as_secs_f64());
+        let mut iter = iter.into_iter();
+        let mut sent = 0;
+        while let Some(value) = iter.next() {
+            if let Err(error) = self.send_deadline(value, deadline) {
+                return Err(SendIterError {
+                    sent,
+                    error,
+                    rest: iter,
+                });
+            }
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    /// Receive up to `max` values from the channel at once.
+    ///
+    /// Yields until at least one value is available (or `timeout` expires), then takes all buffered values
+    /// (but no more than `max`) without yielding. `max` must be greater than zero.
+    pub fn recv_all(&self, max: usize, timeout: Duration) -> Result<Vec<T>, RecvTimeoutError> {
+        assert!(max > 0, "max must be greater than zero");
+        let first = self.recv_deadline(Some(clock() + timeout.as_secs_f64()))?;
+        let mut result = Vec::with_capacity(max.min(self.len() + 1));
+        result.push(first);
+
+        let mut buffer = self.inner.buffer.borrow_mut();
+        while result.len() < max {
+            match buffer.pop_front() {
+                Some(value) => result.push(value),
+                None => break,
+            }
+        }
+        self.inner.writers.broadcast();
+        Ok(result)
+    }
+
+    /// Close the channel.
+    ///
+    /// All fibers waiting on the channel are woken up. Subsequent sends fail, receives return remaining values
+    /// and then fail.
+    pub fn close(&self) {
+        self.inner.is_closed.set(true);
+        self.inner.readers.broadcast();
+        self.inner.writers.broadcast();
+        self.inner.notify_selectors();
+    }
+
+    /// Returns `true` if the channel is closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed.get()
+    }
+
+    /// Returns the number of values in the channel.
+    pub fn len(&self) -> usize {
+        self.inner.buffer.borrow().len()
+    }
+
+    /// Returns `true` if the channel contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns maximum number of values the channel can hold.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Returns the current fill level and the counters of blocked operations, see
+    /// [ChannelMetrics](struct.ChannelMetrics.html).
+    pub fn metrics(&self) -> ChannelMetrics {
+        ChannelMetrics {
+            capacity: self.inner.capacity,
+            len: self.len(),
+            sends_blocked: self.inner.sends_blocked.get(),
+            recvs_blocked: self.inner.recvs_blocked.get(),
+        }
+    }
+
+    /// Split the channel into sending and receiving halves.
+    ///
+    /// The [Sender](struct.Sender.html) can be cloned to feed the channel from several fibers. The channel is closed
+    /// when all senders are dropped (receiver still gets buffered values) or when the receiver is dropped.
+    ///
+    /// Example:
+    /// ```rust
+    /// use tarantool::fiber::channel::Channel;
+    ///
+    /// let (tx, rx) = Channel::new(10).split();
+    /// let tx2 = tx.clone();
+    /// tx.send(1).unwrap();
+    /// tx2.send(2).unwrap();
+    /// drop((tx, tx2));
+    ///
+    /// assert_eq!(rx.recv(), Ok(1));
+    /// assert_eq!(rx.recv(), Ok(2));
+    /// assert!(rx.recv().is_err());
+    /// ```
+    pub fn split(self) -> (Sender<T>, Receiver<T>) {
+        (Sender::new(self.clone()), Receiver { channel: self })
+    }
+
+    fn send_deadline(&self, value: T, deadline: Option<f64>) -> Result<(), SendTimeoutError<T>> {
+        loop {
+            if self.is_closed() {
+                return Err(SendTimeoutError::Closed(value));
+            }
+
+            if self.len() < self.inner.capacity {
+                self.inner.buffer.borrow_mut().push_back(value);
+                self.inner.readers.signal();
+                self.inner.notify_selectors();
+                return Ok(());
+            }
+
+            increment(&self.inner.sends_blocked);
+            if !wait(&self.inner.writers, deadline) {
+                return Err(SendTimeoutError::Timeout(value));
+            }
+        }
+    }
+
+    fn recv_deadline(&self, deadline: Option<f64>) -> Result<T, RecvTimeoutError> {
+        loop {
+            if let Some(value) = self.pop() {
+                return Ok(value);
+            }
+
+            if self.is_closed() {
+                return Err(RecvTimeoutError::Closed);
+            }
+
+            increment(&self.inner.recvs_blocked);
+            if !wait(&self.inner.readers, deadline) {
+                return Err(RecvTimeoutError::Timeout);
+            }
+        }
+    }
+
+    fn pop(&self) -> Option<T> {
+        let value = self.inner.buffer.borrow_mut().pop_front();
+        if value.is_some() {
+            self.inner.writers.signal();
+        }
+        value
+    }
+}
+
+impl<T> Clone for Channel<T> {
+    fn clone(&self) -> Self {
+        Channel {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// The sending half of a channel (see [Channel::split()](struct.Channel.html#method.split)).
+///
+/// Cloning a sender returns a new sender of the same channel. The channel is closed when the last sender is dropped.
+pub struct Sender<T> {
+    channel: Channel<T>,
+}
+
+impl<T> Sender<T> {
+    fn new(channel: Channel<T>) -> Self {
+        let senders = &channel.inner.senders;
+        senders.set(senders.get() + 1);
+        Sender { channel }
+    }
+
+    /// Same as [Channel::send()](struct.Channel.html#method.send).
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.channel.send(value)
+    }
+
+    /// Same as [Channel::send_timeout()](struct.Channel.html#method.send_timeout).
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        self.channel.send_timeout(value, timeout)
+    }
+
+    /// Same as [Channel::send_iter()](struct.Channel.html#method.send_iter).
+    pub fn send_iter<I>(
+        &self,
+        iter: I,
+        timeout: Duration,
+    ) -> Result<usize, SendIterError<T, I::IntoIter>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.channel.send_iter(iter, timeout)
+    }
+
+    /// Returns `true` if the channel is closed (e.g. the receiver is dropped).
+    pub fn is_closed(&self) -> bool {
+        self.channel.is_closed()
+    }
+
+    /// Returns the number of values in the channel.
+    pub fn len(&self) -> usize {
+        self.channel.len()
+    }
+
+    /// Returns `true` if the channel contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.channel.is_empty()
+    }
+
+    /// Returns maximum number of values the channel can hold.
+    pub fn capacity(&self) -> usize {
+        self.channel.capacity()
+    }
+
+    /// Same as [Channel::metrics()](struct.Channel.html#method.metrics).
+    pub fn metrics(&self) -> ChannelMetrics {
+        self.channel.metrics()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Sender::new(self.channel.clone())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let senders = &self.channel.inner.senders;
+        senders.set(senders.get() - 1);
+        if senders.get() == 0 {
+            self.channel.close();
+        }
+    }
+}
+
+/// The receiving half of a channel (see [Channel::split()](struct.Channel.html#method.split)).
+///
+/// The channel is closed when the receiver is dropped.
+pub struct Receiver<T> {
+    channel: Channel<T>,
+}
+
+impl<T> Receiver<T> {
+    /// Same as [Channel::recv()](struct.Channel.html#method.recv): fails when all senders are dropped and the channel
+    /// is empty.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.channel.recv()
+    }
+
+    /// Same as [Channel::recv_timeout()](struct.Channel.html#method.recv_timeout).
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.channel.recv_timeout(timeout)
+    }
+
+    /// Same as [Channel::recv_all()](struct.Channel.html#method.recv_all).
+    pub fn recv_all(&self, max: usize, timeout: Duration) -> Result<Vec<T>, RecvTimeoutError> {
+        self.channel.recv_all(max, timeout)
+    }
+
+    /// Returns `true` if the channel is closed (e.g. all senders are dropped).
+    pub fn is_closed(&self) -> bool {
+        self.channel.is_closed()
+    }
+
+    /// Returns the number of values in the channel.
+    pub fn len(&self) -> usize {
+        self.channel.len()
+    }
+
+    /// Returns `true` if the channel contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.channel.is_empty()
+    }
+
+    /// Same as [Channel::metrics()](struct.Channel.html#method.metrics).
+    pub fn metrics(&self) -> ChannelMetrics {
+        self.channel.metrics()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.channel.close();
+    }
+}
+
+/// Receives values from several channels, preferring channels with higher priority.
+///
+/// When values are available in several channels, [recv()](#method.recv) takes the value from the channel with the
+/// highest priority (channels with equal priorities are checked in the order they were added). This allows e.g. to
+/// handle a shutdown signal before the pending work.
+///
+/// Example:
+/// ```rust
+/// use tarantool::fiber::channel::Channel;
+/// use tarantool::fiber::PrioritySelect;
+///
+/// let data = Channel::new(10);
+/// let control = Channel::new(1);
+/// let mut select = PrioritySelect::new();
+/// select.add(&data, 0).add(&control, 1);
+///
+/// data.send("work").unwrap();
+/// control.send("shutdown").unwrap();
+/// assert_eq!(select.recv(), Ok("shutdown"));
+/// assert_eq!(select.recv(), Ok("work"));
+/// ```
+pub struct PrioritySelect<T> {
+    channels: Vec<(Channel<T>, i32)>,
+}
+
+impl<T> PrioritySelect<T> {
+    /// Create a new select without channels.
+    pub fn new() -> Self {
+        PrioritySelect {
+            channels: Vec::new(),
+        }
+    }
+
+    /// Add a channel with the given `priority` (greater value means higher priority).
+    pub fn add(&mut self, channel: &Channel<T>, priority: i32) -> &mut Self {
+        let pos = self
+            .channels
+            .iter()
+            .position(|(_, p)| *p < priority)
+            .unwrap_or(self.channels.len());
+        self.channels.insert(pos, (channel.clone(), priority));
+        self
+    }
+
+    /// Receive a value from the ready channel with the highest priority. Yields while all channels are empty.
+    ///
+    /// Returns [RecvError](channel/struct.RecvError.html) if all channels are closed and empty (or no channels were
+    /// added).
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.recv_deadline(None).map_err(|_| RecvError)
+    }
+
+    /// Same as [recv()](#method.recv), but gives up after `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.recv_deadline(Some(clock() + timeout.as_secs_f64()))
+    }
+
+    fn recv_deadline(&self, deadline: Option<f64>) -> Result<T, RecvTimeoutError> {
+        loop {
+            for (channel, _) in &self.channels {
+                if let Some(value) = channel.pop() {
+                    return Ok(value);
+                }
+            }
+
+            if self.channels.iter().all(|(channel, _)| channel.is_closed()) {
+                return Err(RecvTimeoutError::Closed);
+            }
+
+            // each waiting fiber registers its own cond, so that the select can be shared between fibers
+            let cond = Rc::new(Cond::new());
+            for (channel, _) in &self.channels {
+                channel.inner.selectors.borrow_mut().push(cond.clone());
+            }
+            let is_woken = wait(&cond, deadline);
+            for (channel, _) in &self.channels {
+                channel
+                    .inner
+                    .selectors
+                    .borrow_mut()
+                    .retain(|c| !Rc::ptr_eq(c, &cond));
+            }
+
+            if !is_woken {
+                return Err(RecvTimeoutError::Timeout);
+            }
+        }
+    }
+}
+
+impl<T> Default for PrioritySelect<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A channel with two lanes: urgent values jump ahead of normal ones.
+///
+/// [recv()](#method.recv) returns urgent values first (in the order they were sent), then normal values. Each lane
+/// holds up to `capacity` values. Cloning returns a new handle to the same channel.
+///
+/// Example:
+/// ```rust
+/// use tarantool::fiber::channel::PriorityChannel;
+///
+/// let channel = PriorityChannel::new(10);
+/// channel.send("data").unwrap();
+/// channel.send_urgent("stop").unwrap();
+/// assert_eq!(channel.recv(), Ok("stop"));
+/// assert_eq!(channel.recv(), Ok("data"));
+/// ```
+pub struct PriorityChannel<T> {
+    normal: Channel<T>,
+    urgent: Channel<T>,
+    select: PrioritySelect<T>,
+}
+
+impl<T> PriorityChannel<T> {
+    /// Create a new channel.
+    ///
+    /// - `capacity` - maximum number of values each lane can hold (must be greater than zero)
+    pub fn new(capacity: usize) -> Self {
+        Self::from_lanes(Channel::new(capacity), Channel::new(capacity))
+    }
+
+    fn from_lanes(normal: Channel<T>, urgent: Channel<T>) -> Self {
+        let mut select = PrioritySelect::new();
+        select.add(&urgent, 1).add(&normal, 0);
+        PriorityChannel {
+            normal,
+            urgent,
+            select,
+        }
+    }
+
+    /// Send a normal value. Yields while the normal lane is full.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.normal.send(value)
+    }
+
+    /// Send an urgent value, which is received before all normal values. Yields while the urgent lane is full.
+    pub fn send_urgent(&self, value: T) -> Result<(), SendError<T>> {
+        self.urgent.send(value)
+    }
+
+    /// Receive a value, urgent values first. Yields while the channel is empty.
+    ///
+    /// Returns [RecvError](struct.RecvError.html) if the channel is closed and empty.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.select.recv()
+    }
+
+    /// Same as [recv()](#method.recv), but gives up after `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.select.recv_timeout(timeout)
+    }
+
+    /// Close the channel (both lanes).
+    pub fn close(&self) {
+        self.normal.close();
+        self.urgent.close();
+    }
+
+    /// Returns `true` if the channel is closed.
+    pub fn is_closed(&self) -> bool {
+        self.normal.is_closed()
+    }
+
+    /// Returns the number of values in the channel (in both lanes).
+    pub fn len(&self) -> usize {
+        self.normal.len() + self.urgent.len()
+    }
+
+    /// Returns `true` if the channel contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for PriorityChannel<T> {
+    fn clone(&self) -> Self {
+        Self::from_lanes(self.normal.clone(), self.urgent.clone())
+    }
+}
+
+/// Snapshot of channel state returned by [Channel::metrics()](struct.Channel.html#method.metrics).
+///
+/// The counters grow each time a sender (receiver) has to yield because the channel is full (empty), so a growing
+/// `sends_blocked` means the consumer doesn't keep up, and a growing `recvs_blocked` means it is starving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelMetrics {
+    /// Maximum number of values the channel can hold
+    pub capacity: usize,
+
+    /// Number of values in the channel
+    pub len: usize,
+
+    /// How many times senders yielded waiting for free space
+    pub sends_blocked: u64,
+
+    /// How many times receivers yielded waiting for a value
+    pub recvs_blocked: u64,
+}
+
+fn increment(counter: &Cell<u64>) {
+    counter.set(counter.get() + 1);
+}
+
+/// Wait for `cond` until `deadline` (measured by [clock()](../fn.clock.html)).
+///
+/// Returns `false` on timeout (or if the fiber is cancelled while waiting without deadline).
+fn wait(cond: &Cond, deadline: Option<f64>) -> bool {
+    match deadline {
+        None => cond.wait(),
+        Some(deadline) => {
+            let timeout = deadline - clock();
+            timeout > 0. && cond.wait_timeout(Duration::from_secs_f64(timeout))
+        }
+    }
+}
+
+/// Error returned by [send()](struct.Channel.html#method.send): the channel is closed.
+///
+/// Contains the value which was not sent.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SendError(..)")
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sending on a closed channel")
+    }
+}
+
+/// Error returned by [send_timeout()](struct.Channel.html#method.send_timeout).
+///
+/// Contains the value which was not sent.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum SendTimeoutError<T> {
+    Timeout(T),
+    Closed(T),
+}
+
+impl<T> fmt::Debug for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendTimeoutError::Timeout(_) => f.write_str("Timeout(..)"),
+            SendTimeoutError::Closed(_) => f.write_str("Closed(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendTimeoutError::Timeout(_) => f.write_str("timed out waiting on send operation"),
+            SendTimeoutError::Closed(_) => f.write_str("sending on a closed channel"),
+        }
+    }
+}
+
+/// Error returned by [send_iter()](struct.Channel.html#method.send_iter).
+pub struct SendIterError<T, I> {
+    /// Number of values sent before the error.
+    pub sent: usize,
+    /// Reason of the failure. Contains the value which was not sent.
+    pub error: SendTimeoutError<T>,
+    /// Values which were not yet taken from the iterator.
+    pub rest: I,
+}
+
+impl<T, I> fmt::Debug for SendIterError<T, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendIterError")
+            .field("sent", &self.sent)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<T, I> fmt::Display for SendIterError<T, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (after {} values sent)", self.error, self.sent)
+    }
+}
+
+/// Error returned by [recv()](struct.Channel.html#method.recv): the channel is closed and empty.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("receiving on a closed channel")
+    }
+}
+
+/// Error returned by [recv_timeout()](struct.Channel.html#method.recv_timeout).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RecvTimeoutError {
+    Timeout,
+    Closed,
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvTimeoutError::Timeout => f.write_str("timed out waiting on receive operation"),
+            RecvTimeoutError::Closed => f.write_str("receiving on a closed channel"),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Fiber-local context values
+//!
+//! A context is an arbitrary value (e.g. a trace id) attached to the current fiber with
+//! [set_context()](fn.set_context.html) and read anywhere down the call stack with [context()](fn.context.html).
+//! [spawn_with_context()](fn.spawn_with_context.html) starts a fiber which sees the given context from the start, so
+//! work done on behalf of a request can be correlated across fibers.
+//!
+//! Example:
+//! ```rust
+//! use tarantool::fiber::{context, set_context, spawn_with_context};
+//!
+//! struct TraceId(u64);
+//!
+//! let _guard = set_context(TraceId(42));
+//! spawn_with_context(context::<TraceId>().unwrap(), || {
+//!     assert_eq!(context::<TraceId>().unwrap().0, 42);
+//! });
+//! ```
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use crate::ffi::tarantool as ffi;
+use crate::fiber::{Builder, Fiber};
+
+thread_local! {
+    // keyed by the address of the fiber: the entry is removed by the guard before the fiber is recycled
+    static CONTEXTS: RefCell<HashMap<usize, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Restores the previous context of the fiber when dropped, see [set_context()](fn.set_context.html).
+pub struct ContextGuard {
+    fiber: usize,
+    previous: Option<Rc<dyn Any>>,
+    // the guard must be dropped in the fiber which created it
+    _marker: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        CONTEXTS.with(|contexts| {
+            let mut contexts = contexts.borrow_mut();
+            match self.previous.take() {
+                Some(previous) => contexts.insert(self.fiber, previous),
+                None => contexts.remove(&self.fiber),
+            };
+        });
+    }
+}
+
+/// Attach `context` to the current fiber. The previous context is restored when the returned guard is dropped.
+pub fn set_context<C>(context: C) -> ContextGuard
+where
+    C: 'static,
+{
+    set_context_rc(Rc::new(context))
+}
+
+/// Returns the context of the current fiber if it is set and has type `C`.
+pub fn context<C>() -> Option<Rc<C>>
+where
+    C: 'static,
+{
+    let fiber = current_fiber();
+    let context = CONTEXTS.with(|contexts| contexts.borrow().get(&fiber).cloned())?;
+    context.downcast::<C>().ok()
+}
+
+/// Start a new fiber running `f` with `context` attached to it.
+///
+/// To pass the context of the current fiber down, use `spawn_with_context(context::<C>().unwrap(), f)`: the child
+/// shares the same value. The context is detached when `f` returns.
+pub fn spawn_with_context<C, F>(context: Rc<C>, f: F)
+where
+    C: 'static,
+    F: FnOnce() + 'static,
+{
+    let task = ContextTask {
+        context,
+        f: Box::new(f),
+    };
+    // the fiber keeps a pointer to the callback until it finishes, which may happen after this function returns;
+    // the callback is a zero-sized fn item, so leaking it doesn't allocate
+    let callback = Box::leak(Box::new(run_with_context));
+    let mut fiber: Fiber<ContextTask> = Builder::new().build(callback);
+    fiber.start(task);
+}
+
+struct ContextTask {
+    context: Rc<dyn Any>,
+    f: Box<dyn FnOnce()>,
+}
+
+fn run_with_context(task: Box<ContextTask>) -> i32 {
+    let ContextTask { context, f } = *task;
+    let _guard = set_context_rc(context);
+    f();
+    0
+}
+
+fn set_context_rc(context: Rc<dyn Any>) -> ContextGuard {
+    let fiber = current_fiber();
+    let previous = CONTEXTS.with(|contexts| contexts.borrow_mut().insert(fiber, context));
+    ContextGuard {
+        fiber,
+        previous,
+        _marker: PhantomData,
+    }
+}
+
+fn current_fiber() -> usize {
+    unsafe { ffi::fiber_self() as usize }
+}
@@ -0,0 +1,170 @@
+//! Mutex and condition variable for fibers
+//!
+//! [Mutex](struct.Mutex.html) is a [Latch](../struct.Latch.html) which owns the data it protects.
+//! [CondVar](struct.CondVar.html) pairs a mutex with a [Cond](../struct.Cond.html), so fibers can wait until
+//! the protected data satisfies some predicate.
+//!
+//! Example:
+//! ```rust
+//! use tarantool::fiber::CondVar;
+//!
+//! let ready = CondVar::new(false);
+//!
+//! // in producer fiber
+//! *ready.lock() = true;
+//! ready.notify_all();
+//!
+//! // in consumer fiber
+//! let guard = ready.wait_while(|ready| !*ready);
+//! ```
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+use crate::fiber::{clock, Cond, Latch, LatchGuard};
+
+/// A mutual exclusion primitive for fibers protecting the data of type `T`.
+///
+/// The data can only be accessed through the guard returned from [lock()](#method.lock).
+pub struct Mutex<T> {
+    latch: Latch,
+    data: UnsafeCell<T>,
+}
+
+impl<T> Mutex<T> {
+    /// Creates a new mutex in an unlocked state.
+    pub fn new(value: T) -> Self {
+        Mutex {
+            latch: Latch::new(),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Acquires the mutex. Yields until the mutex is available.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        MutexGuard {
+            mutex: self,
+            _lock: self.latch.lock(),
+        }
+    }
+
+    /// Attempts to acquire the mutex without yielding.
+    ///
+    /// Returns `None` if the mutex is locked by another fiber.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        self.latch.try_lock().map(|lock| MutexGuard {
+            mutex: self,
+            _lock: lock,
+        })
+    }
+
+    /// Consumes the mutex, returning the underlying data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// An RAII implementation of a "scoped lock" of a mutex. When this structure is dropped (falls out of scope),
+/// the lock will be unlocked.
+///
+/// The data protected by the mutex can be accessed through this guard via its `Deref` and `DerefMut`
+/// implementations.
+pub struct MutexGuard<'a, T> {
+    mutex: &'a Mutex<T>,
+    _lock: LatchGuard,
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+/// A condition variable paired with the [Mutex](struct.Mutex.html) protecting the data of type `T`.
+pub struct CondVar<T> {
+    mutex: Mutex<T>,
+    cond: Cond,
+}
+
+impl<T> CondVar<T> {
+    /// Creates a new condition variable protecting `value`.
+    pub fn new(value: T) -> Self {
+        CondVar {
+            mutex: Mutex::new(value),
+            cond: Cond::new(),
+        }
+    }
+
+    /// Acquires the mutex. Yields until the mutex is available.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.mutex.lock()
+    }
+
+    /// Releases the mutex held by `guard` and yields until the condition variable is notified, then re-acquires
+    /// the mutex.
+    ///
+    /// Spurious wakeups are possible, use [wait_while()](#method.wait_while) to wait for a predicate.
+    pub fn wait<'a>(&'a self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        drop(guard);
+        self.cond.wait();
+        self.mutex.lock()
+    }
+
+    /// Acquires the mutex and yields until `condition` returns `false`.
+    ///
+    /// The mutex is released while the fiber waits for notification and is held when `condition` is called and when
+    /// this function returns.
+    pub fn wait_while<F>(&self, mut condition: F) -> MutexGuard<'_, T>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut guard = self.mutex.lock();
+        while condition(&mut *guard) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+
+    /// Same as [wait_while()](#method.wait_while), but gives up after `timeout`.
+    ///
+    /// Returns the guard and `true` if the timeout elapsed while `condition` still returned `true`.
+    pub fn wait_timeout_while<F>(
+        &self,
+        timeout: Duration,
+        mut condition: F,
+    ) -> (MutexGuard<'_, T>, bool)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let deadline = clock() + timeout.as_secs_f64();
+        let mut guard = self.mutex.lock();
+        while condition(&mut *guard) {
+            let timeout = deadline - clock();
+            if timeout <= 0. {
+                return (guard, true);
+            }
+            drop(guard);
+            self.cond.wait_timeout(Duration::from_secs_f64(timeout));
+            guard = self.mutex.lock();
+        }
+        (guard, false)
+    }
+
+    /// Wakes up one fiber waiting on this condition variable.
+    pub fn notify_one(&self) {
+        self.cond.signal();
+    }
+
+    /// Wakes up all fibers waiting on this condition variable.
+    pub fn notify_all(&self) {
+        self.cond.broadcast();
+    }
+}
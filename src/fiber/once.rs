@@ -0,0 +1,93 @@
+//! One-time initialization for fibers
+//!
+//! [Once](struct.Once.html) runs an initializer exactly once, even if several fibers race to run it: the first fiber
+//! runs it, the others yield until it completes. Unlike `std::sync::Once`, waiting fibers never block the thread.
+//!
+//! Example:
+//! ```rust
+//! use tarantool::fiber::Once;
+//!
+//! let init = Once::new();
+//!
+//! // in each fiber
+//! init.call_once(|| {
+//!     // build a shared cache (may yield)
+//! });
+//! assert!(init.is_completed());
+//! ```
+use std::cell::Cell;
+
+use crate::fiber::Cond;
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Incomplete,
+    Running,
+    Complete,
+}
+
+/// A cooperative one-time initializer for fibers.
+pub struct Once {
+    state: Cell<State>,
+    cond: Cond,
+}
+
+impl Once {
+    /// Creates a new `Once` which has not run yet.
+    pub fn new() -> Self {
+        Once {
+            state: Cell::new(State::Incomplete),
+            cond: Cond::new(),
+        }
+    }
+
+    /// Runs `f` if it's the first call. Yields while another fiber is running its initializer.
+    ///
+    /// When this function returns, the initializer has completed. If the initializer panics, the `Once` stays
+    /// incomplete and one of the waiting fibers runs its own initializer.
+    pub fn call_once<F>(&self, f: F)
+    where
+        F: FnOnce(),
+    {
+        loop {
+            match self.state.get() {
+                State::Complete => return,
+                State::Running => {
+                    self.cond.wait();
+                }
+                State::Incomplete => break,
+            }
+        }
+
+        self.state.set(State::Running);
+        let guard = RunningGuard { once: self };
+        f();
+        self.state.set(State::Complete);
+        drop(guard);
+    }
+
+    /// Returns `true` if an initializer has completed.
+    pub fn is_completed(&self) -> bool {
+        self.state.get() == State::Complete
+    }
+}
+
+impl Default for Once {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wakes up waiting fibers when the initializer is done (or panicked).
+struct RunningGuard<'a> {
+    once: &'a Once,
+}
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        if self.once.state.get() == State::Running {
+            self.once.state.set(State::Incomplete);
+        }
+        self.once.cond.broadcast();
+    }
+}
@@ -0,0 +1,95 @@
+//! Counting semaphore for fibers
+//!
+//! [Semaphore](struct.Semaphore.html) limits the number of fibers doing something at the same time, e.g. the number
+//! of concurrent outgoing requests. Waiting fibers yield, so the thread is never blocked.
+//!
+//! Example:
+//! ```rust
+//! use tarantool::fiber::Semaphore;
+//!
+//! let semaphore = Semaphore::new(3);
+//!
+//! // in each worker fiber
+//! let _permit = semaphore.acquire();
+//! // at most 3 fibers get here at the same time
+//! ```
+use std::cell::Cell;
+use std::time::Duration;
+
+use crate::fiber::{clock, Cond};
+
+/// A counting semaphore for fibers.
+pub struct Semaphore {
+    permits: Cell<usize>,
+    cond: Cond,
+}
+
+impl Semaphore {
+    /// Creates a new semaphore with `permits` available permits.
+    pub fn new(permits: usize) -> Self {
+        Semaphore {
+            permits: Cell::new(permits),
+            cond: Cond::new(),
+        }
+    }
+
+    /// Acquires a permit. Yields until a permit is available.
+    ///
+    /// The permit is released when the returned guard is dropped.
+    pub fn acquire(&self) -> SemaphorePermit<'_> {
+        while self.permits.get() == 0 {
+            self.cond.wait();
+        }
+        self.take_permit()
+    }
+
+    /// Same as [acquire()](#method.acquire), but gives up after `timeout`.
+    ///
+    /// Returns `None` if no permit became available in time.
+    pub fn acquire_timeout(&self, timeout: Duration) -> Option<SemaphorePermit<'_>> {
+        let deadline = clock() + timeout.as_secs_f64();
+        while self.permits.get() == 0 {
+            let timeout = deadline - clock();
+            if timeout <= 0. {
+                return None;
+            }
+            self.cond.wait_timeout(Duration::from_secs_f64(timeout));
+        }
+        Some(self.take_permit())
+    }
+
+    /// Acquires a permit without yielding.
+    ///
+    /// Returns `None` if there are no available permits.
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        if self.permits.get() == 0 {
+            None
+        } else {
+            Some(self.take_permit())
+        }
+    }
+
+    /// Returns the number of currently available permits.
+    pub fn available_permits(&self) -> usize {
+        self.permits.get()
+    }
+
+    fn take_permit(&self) -> SemaphorePermit<'_> {
+        self.permits.set(self.permits.get() - 1);
+        SemaphorePermit { semaphore: self }
+    }
+}
+
+/// A permit acquired from a [Semaphore](struct.Semaphore.html). The permit is released when this structure is
+/// dropped (falls out of scope).
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        let permits = &self.semaphore.permits;
+        permits.set(permits.get() + 1);
+        self.semaphore.cond.signal();
+    }
+}
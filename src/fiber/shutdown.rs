@@ -0,0 +1,112 @@
+//! Graceful shutdown of worker fibers
+//!
+//! [ShutdownBarrier](struct.ShutdownBarrier.html) tells a pool of worker fibers to stop and waits until all of them
+//! exit. Workers check [should_stop()](struct.ShutdownBarrier.html#method.should_stop) in their loop and call
+//! [worker_done()](struct.ShutdownBarrier.html#method.worker_done) on exit.
+//!
+//! Example:
+//! ```rust
+//! use std::rc::Rc;
+//! use std::time::Duration;
+//! use tarantool::fiber::{Fiber, ShutdownBarrier};
+//!
+//! let barrier = Rc::new(ShutdownBarrier::new(1));
+//! let mut worker = Fiber::new("worker", &mut |barrier: Box<Rc<ShutdownBarrier>>| {
+//!     while !barrier.wait_stop(Duration::from_secs(1)) {
+//!         // do some work
+//!     }
+//!     barrier.worker_done();
+//!     0
+//! });
+//! worker.start(barrier.clone());
+//!
+//! assert!(barrier.shutdown(Duration::from_secs(5)));
+//! ```
+use std::cell::Cell;
+use std::time::Duration;
+
+use crate::fiber::{clock, Cond};
+
+/// Stops a pool of worker fibers and waits for them to exit.
+pub struct ShutdownBarrier {
+    workers: Cell<usize>,
+    is_stopping: Cell<bool>,
+    // signalled on shutdown
+    stop: Cond,
+    // signalled when a worker exits
+    done: Cond,
+}
+
+impl ShutdownBarrier {
+    /// Creates a barrier for `workers` worker fibers.
+    pub fn new(workers: usize) -> Self {
+        ShutdownBarrier {
+            workers: Cell::new(workers),
+            is_stopping: Cell::new(false),
+            stop: Cond::new(),
+            done: Cond::new(),
+        }
+    }
+
+    /// Registers one more worker, e.g. when the pool grows.
+    pub fn add_worker(&self) {
+        self.workers.set(self.workers.get() + 1);
+    }
+
+    /// Returns `true` once [shutdown()](#method.shutdown) is called. Workers should exit their loop then.
+    pub fn should_stop(&self) -> bool {
+        self.is_stopping.get()
+    }
+
+    /// Yields until [shutdown()](#method.shutdown) is called or `timeout` expires, so an idle worker can pause
+    /// between iterations without delaying the shutdown.
+    ///
+    /// Returns the same as [should_stop()](#method.should_stop).
+    pub fn wait_stop(&self, timeout: Duration) -> bool {
+        let deadline = clock() + timeout.as_secs_f64();
+        while !self.is_stopping.get() {
+            let timeout = deadline - clock();
+            if timeout <= 0. {
+                break;
+            }
+            self.stop.wait_timeout(Duration::from_secs_f64(timeout));
+        }
+        self.is_stopping.get()
+    }
+
+    /// Acknowledges the shutdown: must be called by each worker when it exits.
+    pub fn worker_done(&self) {
+        let workers = self.workers.get();
+        assert!(
+            workers > 0,
+            "worker_done() is called more times than there are workers"
+        );
+        self.workers.set(workers - 1);
+        if workers == 1 {
+            self.done.broadcast();
+        }
+    }
+
+    /// Returns the number of workers which haven't exited yet.
+    pub fn remaining(&self) -> usize {
+        self.workers.get()
+    }
+
+    /// Tells the workers to stop and yields until all of them exit (or `timeout` expires).
+    ///
+    /// Returns `true` if all the workers called [worker_done()](#method.worker_done) in time.
+    pub fn shutdown(&self, timeout: Duration) -> bool {
+        let deadline = clock() + timeout.as_secs_f64();
+        if !self.is_stopping.replace(true) {
+            self.stop.broadcast();
+        }
+        while self.workers.get() > 0 {
+            let timeout = deadline - clock();
+            if timeout <= 0. {
+                return false;
+            }
+            self.done.wait_timeout(Duration::from_secs_f64(timeout));
+        }
+        true
+    }
+}
@@ -7,14 +7,26 @@
 //! See also:
 //! - [Indexes](https://www.tarantool.io/en/doc/latest/book/box/data_model/#indexes)
 //! - [Lua reference: Submodule box.index](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_index/)
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::Bound;
 use std::os::raw::c_char;
 use std::ptr::null_mut;
 
 use num_traits::ToPrimitive;
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
 
-use crate::error::{Error, TarantoolError};
+use crate::error::{Error, TarantoolError, TarantoolErrorCode, TransactionError};
 use crate::ffi::tarantool as ffi;
-use crate::tuple::{AsTuple, Tuple, TupleBuffer};
+use crate::space::{Space, SystemSpace};
+use crate::tuple::{
+    decode_fields, project_fields, raw_bytes, AsTuple, FieldType, KeyDef, KeyDefItem, ToKey, Tuple,
+    TupleBuffer,
+};
 
 /// An index is a group of key values and pointers.
 pub struct Index {
@@ -22,6 +34,9 @@ pub struct Index {
     index_id: u32,
 }
 
+/// How many times [Index::modify](struct.Index.html#method.modify) retries a transaction after a conflict.
+const MODIFY_MAX_RETRIES: usize = 3;
+
 /// Controls how to iterate over tuples in an index.
 /// Different index types support different iterator types.
 /// For example, one can start iteration from a particular value
@@ -79,6 +94,28 @@ pub enum IteratorType {
     Neighbor = 11,
 }
 
+/// Numbering of fields in update operations (`[['=', field_no, value], ...]`).
+///
+/// Off-by-one mistakes here silently modify wrong fields, so choose it explicitly with
+/// [update_with_base()](struct.Index.html#method.update_with_base) or
+/// [upsert_with_base()](struct.Index.html#method.upsert_with_base):
+/// - `One` - the first field is `1` (as in Lua API), this is the default
+/// - `Zero` - the first field is `0` (as in IPROTO and [tuple.field()](../tuple/struct.Tuple.html#method.field)),
+///   this is what plain [update()](struct.Index.html#method.update) and [upsert()](struct.Index.html#method.upsert) use
+///   to stay compatible with the existing callers
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IndexBase {
+    Zero = 0,
+    One = 1,
+}
+
+impl Default for IndexBase {
+    fn default() -> Self {
+        IndexBase::One
+    }
+}
+
 /// List of options for new or updated index.
 ///
 /// For details see [space_object:create_index - options](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_space/create_index/).
@@ -157,6 +194,30 @@ pub enum IndexType {
     Rtree,
 }
 
+impl IndexType {
+    /// Returns `true` if indexes of this type can be iterated with `iterator_type` (e.g. `HASH` indexes don't support
+    /// range iterators except `GT`).
+    pub fn supports(&self, iterator_type: IteratorType) -> bool {
+        use IteratorType::*;
+        match self {
+            IndexType::Hash => matches!(iterator_type, Eq | All | GT),
+            IndexType::Tree => matches!(iterator_type, Eq | Req | All | LT | LE | GE | GT),
+            IndexType::Bitset => {
+                matches!(
+                    iterator_type,
+                    Eq | All | BitsAllSet | BitsAnySet | BitsAllNotSet
+                )
+            }
+            IndexType::Rtree => {
+                matches!(
+                    iterator_type,
+                    Eq | All | LT | LE | GE | GT | Overlaps | Neighbor
+                )
+            }
+        }
+    }
+}
+
 /// Type of index part.
 #[derive(Copy, Clone, Debug, Serialize)]
 pub enum IndexFieldType {
@@ -202,11 +263,99 @@ pub enum RtreeIndexDistanceType {
     Manhattan,
 }
 
+impl fmt::Debug for Index {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("Index");
+        debug.field("id", &self.index_id);
+        if let Some(name) = self.meta_name() {
+            debug.field("name", &name);
+        }
+        debug.field("space_id", &self.space_id).finish()
+    }
+}
+
 impl Index {
     pub(crate) fn new(space_id: u32, index_id: u32) -> Self {
         Index { space_id, index_id }
     }
 
+    /// Read index name from `_vindex` system space.
+    fn meta_name(&self) -> Option<String> {
+        let vindex: Space = SystemSpace::VIndex.into();
+        vindex
+            .get(&(self.space_id, self.index_id))
+            .ok()??
+            .field::<String>(2)
+            .ok()?
+    }
+
+    /// Read index type from `_vindex` system space.
+    fn meta_type(&self) -> Option<IndexType> {
+        let vindex: Space = SystemSpace::VIndex.into();
+        let index_type = vindex
+            .get(&(self.space_id, self.index_id))
+            .ok()??
+            .field::<String>(3)
+            .ok()??;
+        match index_type.to_uppercase().as_str() {
+            "HASH" => Some(IndexType::Hash),
+            "TREE" => Some(IndexType::Tree),
+            "BITSET" => Some(IndexType::Bitset),
+            "RTREE" => Some(IndexType::Rtree),
+            _ => None,
+        }
+    }
+
+    /// Build key definition of the index from its parts in `_vindex` system space.
+    fn meta_key_def(&self) -> Result<KeyDef, Error> {
+        Ok(KeyDef::new(self.meta_key_parts()?))
+    }
+
+    /// Read the index definition from `_vindex` system space.
+    fn meta(&self) -> Result<Tuple, Error> {
+        let vindex: Space = SystemSpace::VIndex.into();
+        match vindex.get(&(self.space_id, self.index_id))? {
+            Some(index) => Ok(index),
+            None => {
+                set_error!(TarantoolErrorCode::NoSuchIndexID, "{}", self.index_id);
+                Err(TarantoolError::last().into())
+            }
+        }
+    }
+
+    /// Read the index parts from `_vindex` system space.
+    fn meta_key_parts(&self) -> Result<Vec<KeyDefItem>, Error> {
+        let parts = self.meta()?.field::<Vec<JsonValue>>(5)?.unwrap_or_default();
+        parse_key_parts(parts)
+    }
+
+    /// Prepare to look up the tuples of the space in this index, see
+    /// [Space::verify_indexes](../space/struct.Space.html#method.verify_indexes). Returns `None` for `RTREE` indexes,
+    /// which can't find a tuple by its key, and for the indexes which keys are not whole tuple fields: functional
+    /// indexes and indexes with JSON path (or multikey `[*]`) parts.
+    pub(crate) fn verifier(&self) -> Result<Option<IndexVerifier>, Error> {
+        if let Some(IndexType::Rtree) = self.meta_type() {
+            return Ok(None);
+        }
+        let meta = self.meta()?;
+        let opts = meta.field::<JsonValue>(4)?.unwrap_or_default();
+        if opts.get("func").is_some() {
+            return Ok(None);
+        }
+        let parts = meta.field::<Vec<JsonValue>>(5)?.unwrap_or_default();
+        if parts.iter().any(|part| part.get("path").is_some()) {
+            return Ok(None);
+        }
+        Ok(Some(IndexVerifier {
+            index: Index::new(self.space_id, self.index_id),
+            name: self.meta_name().unwrap_or_default(),
+            key_fields: parse_key_parts(parts)?
+                .iter()
+                .map(|part| part.field_id)
+                .collect(),
+        }))
+    }
+
     // Drops index.
     #[cfg(feature = "schema")]
     pub fn drop(&self) -> Result<(), Error> {
@@ -222,9 +371,9 @@ impl Index {
     /// Returns a tuple or `None` if index is empty
     pub fn get<K>(&self, key: &K) -> Result<Option<Tuple>, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
-        let key_buf = key.serialize_as_tuple().unwrap();
+        let key_buf = key.serialize_as_key().unwrap();
         let key_buf_ptr = key_buf.as_ptr() as *const c_char;
         let mut result_ptr = null_mut::<ffi::BoxTuple>();
 
@@ -255,11 +404,20 @@ impl Index {
     ///
     /// - `type` - iterator type
     /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
+    ///
+    /// Returns `Error::UnsupportedIterator` if the index type doesn't support `iterator_type` (e.g. `GE` on a `HASH`
+    /// index). The index type is checked only if the iterator can't be created, so successful selects don't pay for
+    /// it.
+    ///
+    /// **Note:** an iterator opened inside a transaction must not outlive it: iterating with `for` (i.e.
+    /// [next()](struct.IndexIterator.html#method.next)) after the transaction has ended stops the iteration and logs
+    /// the error. Use [try_next()](struct.IndexIterator.html#method.try_next) to get `TransactionError::Ended`
+    /// instead.
     pub fn select<K>(&self, iterator_type: IteratorType, key: &K) -> Result<IndexIterator, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
-        let key_buf = key.serialize_as_tuple().unwrap();
+        let key_buf = key.serialize_as_key().unwrap();
         let key_buf_ptr = key_buf.as_ptr() as *const c_char;
 
         let ptr = unsafe {
@@ -273,15 +431,213 @@ impl Index {
         };
 
         if ptr.is_null() {
-            return Err(TarantoolError::last().into());
+            let error = TarantoolError::last();
+            return Err(match self.meta_type() {
+                Some(index_type) if !index_type.supports(iterator_type) => {
+                    Error::UnsupportedIterator {
+                        index_type,
+                        iterator: iterator_type,
+                    }
+                }
+                _ => error.into(),
+            });
         }
 
         Ok(IndexIterator {
             ptr,
             _key_data: key_buf,
+            txn_id: current_txn_id(),
+        })
+    }
+
+    /// Create a [BiCursor](struct.BiCursor.html) stepping over this index in both directions, starting before the
+    /// first tuple.
+    pub fn cursor(&self) -> BiCursor {
+        BiCursor {
+            index: Index::new(self.space_id, self.index_id),
+            key: None,
+        }
+    }
+
+    /// Same as [select()](#method.select), but the number of tuples is counted upfront (see [count()](#method.count)),
+    /// so the returned iterator implements `ExactSizeIterator`, e.g. to pre-size a collection.
+    ///
+    /// The count is exact as long as the iteration doesn't yield: tuples inserted later are not returned beyond the
+    /// counted number, and the iteration ends early if some tuples are deleted.
+    pub fn select_counted<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+    ) -> Result<CountedIndexIterator, Error>
+    where
+        K: ToKey,
+    {
+        let remaining = self.count(iterator_type, key)?;
+        Ok(CountedIndexIterator {
+            inner: self.select(iterator_type, key)?,
+            remaining,
+        })
+    }
+
+    /// Same as [select()](#method.select), but collects the tuples into `buf` instead of returning an iterator.
+    ///
+    /// `buf` is cleared first and keeps its capacity, so reusing one buffer in a hot loop avoids allocating a new
+    /// vector on every call. Returns the number of selected tuples.
+    pub fn select_into<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+        buf: &mut Vec<Tuple>,
+    ) -> Result<usize, Error>
+    where
+        K: ToKey,
+    {
+        buf.clear();
+        let mut iterator = self.select(iterator_type, key)?;
+        while let Some(tuple) = iterator.try_next()? {
+            buf.push(tuple);
+        }
+        Ok(buf.len())
+    }
+
+    /// Same as [select_into()](#method.select_into), but fails with `Error::TooManyRows` as soon as more than
+    /// `max_rows` tuples are selected, so a query matching a huge part of the space doesn't exhaust the memory.
+    ///
+    /// `buf` is left empty on error. Returns the number of selected tuples.
+    pub fn select_into_capped<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+        max_rows: usize,
+        buf: &mut Vec<Tuple>,
+    ) -> Result<usize, Error>
+    where
+        K: ToKey,
+    {
+        buf.clear();
+        let mut iterator = self.select(iterator_type, key)?;
+        while let Some(tuple) = iterator.try_next()? {
+            if buf.len() == max_rows {
+                buf.clear();
+                return Err(Error::TooManyRows { max_rows });
+            }
+            buf.push(tuple);
+        }
+        Ok(buf.len())
+    }
+
+    /// Select tuples with keys between `start` and `end`, e.g. `index.range(Included(&(10,)), Excluded(&(20,)))`
+    /// for the half-open range `[10, 20)`.
+    ///
+    /// Tuples are returned in ascending order, so this requires a `TREE` index. The iteration stops at the first tuple
+    /// beyond `end`. Keys are compared according to the index parts types (collations are not taken into account).
+    pub fn range<K>(&self, start: Bound<&K>, end: Bound<&K>) -> Result<IndexRange, Error>
+    where
+        K: ToKey,
+    {
+        let inner = match start {
+            Bound::Included(key) => self.select(IteratorType::GE, key)?,
+            Bound::Excluded(key) => self.select(IteratorType::GT, key)?,
+            Bound::Unbounded => self.select(IteratorType::GE, &())?,
+        };
+        let end = match end {
+            Bound::Included(key) => Bound::Included(key.serialize_as_key()?),
+            Bound::Excluded(key) => Bound::Excluded(key.serialize_as_key()?),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let key_def = match end {
+            Bound::Unbounded => None,
+            _ => Some(self.meta_key_def()?),
+        };
+        Ok(IndexRange {
+            inner,
+            end,
+            key_def,
+            is_done: false,
+        })
+    }
+
+    /// Select tuples and decode each of them into `T` directly from the tuple memory.
+    ///
+    /// Unlike [select](#method.select) followed by [Tuple::into_struct](../tuple/struct.Tuple.html#method.into_struct),
+    /// no intermediate [Tuple](../tuple/struct.Tuple.html) is created and tuple data is not copied
+    /// into a temporary buffer.
+    ///
+    /// - `type` - iterator type
+    /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
+    pub fn select_stream<T, K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+    ) -> Result<IndexStream<T>, Error>
+    where
+        T: DeserializeOwned,
+        K: ToKey,
+    {
+        Ok(IndexStream {
+            inner: self.select(iterator_type, key)?,
+            is_failed: false,
+            _marker: PhantomData,
         })
     }
 
+    /// Select tuples and collect them into a `HashMap`.
+    ///
+    /// - `type` - iterator type
+    /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
+    /// - `key_field` - zero-based number of the field used as a map key (decoded as `K`)
+    ///
+    /// Each tuple is decoded as `V`. If several tuples have the same value of `key_field`, the last one wins.
+    /// Fails if `key_field` is missing in some tuple.
+    pub fn select_map<K, V, Q>(
+        &self,
+        iterator_type: IteratorType,
+        key: &Q,
+        key_field: u32,
+    ) -> Result<HashMap<K, V>, Error>
+    where
+        K: DeserializeOwned + Eq + Hash,
+        V: DeserializeOwned,
+        Q: ToKey,
+    {
+        let mut result = HashMap::new();
+        for tuple in self.select(iterator_type, key)? {
+            let map_key = required_field::<K>(&tuple, key_field)?;
+            result.insert(map_key, tuple.into_struct::<V>()?);
+        }
+        Ok(result)
+    }
+
+    /// Select tuples and group them by the value of a field.
+    ///
+    /// - `type` - iterator type
+    /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
+    /// - `group_field` - zero-based number of the field used as a group key (decoded as `K`)
+    ///
+    /// Each tuple is decoded as `V`. Tuples within a group keep the order in which the iterator returned them.
+    /// Fails if `group_field` is missing in some tuple.
+    pub fn select_grouped<K, V, Q>(
+        &self,
+        iterator_type: IteratorType,
+        key: &Q,
+        group_field: u32,
+    ) -> Result<HashMap<K, Vec<V>>, Error>
+    where
+        K: DeserializeOwned + Eq + Hash,
+        V: DeserializeOwned,
+        Q: ToKey,
+    {
+        let mut result: HashMap<K, Vec<V>> = HashMap::new();
+        for tuple in self.select(iterator_type, key)? {
+            let group_key = required_field::<K>(&tuple, group_field)?;
+            result
+                .entry(group_key)
+                .or_default()
+                .push(tuple.into_struct::<V>()?);
+        }
+        Ok(result)
+    }
+
     /// Delete a tuple identified by a key.
     ///
     /// Same as [space.delete()](../space/struct.Space.html#method.delete), but key is searched in this index instead
@@ -292,9 +648,9 @@ impl Index {
     /// Returns the deleted tuple
     pub fn delete<K>(&mut self, key: &K) -> Result<Option<Tuple>, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
-        let key_buf = key.serialize_as_tuple().unwrap();
+        let key_buf = key.serialize_as_key().unwrap();
         let key_buf_ptr = key_buf.as_ptr() as *const c_char;
         let mut result_ptr = null_mut::<ffi::BoxTuple>();
 
@@ -318,6 +674,84 @@ impl Index {
         })
     }
 
+    /// Read-modify-write a tuple atomically.
+    ///
+    /// Gets the tuple by `key` from this index, decodes it into `T`, applies `f` to it and replaces the tuple with
+    /// the modified value. Unless already inside a transaction, all of this runs in a new transaction, which is
+    /// retried (calling `f` again) if it fails to commit due to a conflict.
+    ///
+    /// Returns the modified value, or `None` if there is no such tuple (then `f` is not called). `f` must not change
+    /// the primary key: `IllegalParams` error is returned then and nothing is written.
+    ///
+    /// Example:
+    /// ```rust
+    /// let account: Option<Account> = index.modify(&(id,), |account: &mut Account| account.balance += 10)?;
+    /// ```
+    pub fn modify<T, K, F>(&mut self, key: &K, mut f: F) -> Result<Option<T>, Error>
+    where
+        T: AsTuple + DeserializeOwned,
+        K: ToKey,
+        F: FnMut(&mut T),
+    {
+        if unsafe { ffi::box_txn() } {
+            return self.modify_in_txn(key, &mut f);
+        }
+
+        let mut retries = 0;
+        loop {
+            if unsafe { ffi::box_txn_begin() } < 0 {
+                return Err(TarantoolError::last().into());
+            }
+
+            let result = match self.modify_in_txn(key, &mut f) {
+                Ok(result) => result,
+                Err(err) => {
+                    unsafe { ffi::box_txn_rollback() };
+                    return Err(err);
+                }
+            };
+
+            if unsafe { ffi::box_txn_commit() } < 0 {
+                let err = TarantoolError::last();
+                if err.error_code() == TarantoolErrorCode::TransactionConflict
+                    && retries < MODIFY_MAX_RETRIES
+                {
+                    retries += 1;
+                    continue;
+                }
+                return Err(err.into());
+            }
+            return Ok(result);
+        }
+    }
+
+    fn modify_in_txn<T, K, F>(&mut self, key: &K, f: &mut F) -> Result<Option<T>, Error>
+    where
+        T: AsTuple + DeserializeOwned,
+        K: ToKey,
+        F: FnMut(&mut T),
+    {
+        let old_tuple = match self.get(key)? {
+            None => return Ok(None),
+            Some(tuple) => tuple,
+        };
+        let mut value = old_tuple.as_struct::<T>()?;
+        f(&mut value);
+
+        // replacing a tuple with another primary key would keep the old one
+        let mut space = Space::new(self.space_id);
+        let primary_key = space.primary_key();
+        let new_tuple = Tuple::from_struct(&value)?;
+        ensure!(
+            primary_key.try_extract_key(&old_tuple)?.as_raw_bytes()
+                == primary_key.try_extract_key(&new_tuple)?.as_raw_bytes(),
+            TarantoolErrorCode::IllegalParams,
+            "Primary key must not be changed by modify()"
+        );
+        space.replace(&new_tuple)?;
+        Ok(Some(value))
+    }
+
     /// Update a tuple.
     ///
     /// Same as [space.update()](../space/struct.Space.html#method.update), but key is searched in this index instead
@@ -326,15 +760,38 @@ impl Index {
     /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
     /// - `ops` - encoded operations in MsgPack array format, e.g. `[['=', field_id, value], ['!', 2, 'xxx']]`
     ///
+    /// **Note:** field numbers in `ops` are **zero-based** here (unlike Lua API where they start from 1).
+    /// Use [index.update_with_base()](#method.update_with_base) to choose numbering explicitly.
+    ///
     /// Returns a new tuple.
     ///
     /// See also: [index.upsert()](#method.upsert)
     pub fn update<K, Op>(&mut self, key: &K, ops: &Vec<Op>) -> Result<Option<Tuple>, Error>
     where
-        K: AsTuple,
+        K: ToKey,
         Op: AsTuple,
     {
-        let key_buf = key.serialize_as_tuple().unwrap();
+        self.update_with_base(key, ops, IndexBase::Zero)
+    }
+
+    /// Update a tuple using explicitly specified numbering of fields in `ops`.
+    ///
+    /// Same as [index.update()](#method.update), but field numbers in `ops` are interpreted according to
+    /// `index_base`: `IndexBase::One` (as in Lua API) means the first field is `1`,
+    /// `IndexBase::Zero` means the first field is `0`.
+    ///
+    /// See also: [IndexBase](enum.IndexBase.html)
+    pub fn update_with_base<K, Op>(
+        &mut self,
+        key: &K,
+        ops: &Vec<Op>,
+        index_base: IndexBase,
+    ) -> Result<Option<Tuple>, Error>
+    where
+        K: ToKey,
+        Op: AsTuple,
+    {
+        let key_buf = key.serialize_as_key().unwrap();
         let key_buf_ptr = key_buf.as_ptr() as *const c_char;
         let ops_buf = ops.serialize_as_tuple().unwrap();
         let ops_buf_ptr = ops_buf.as_ptr() as *const c_char;
@@ -348,7 +805,7 @@ impl Index {
                 key_buf_ptr.offset(key_buf.len() as isize),
                 ops_buf_ptr,
                 ops_buf_ptr.offset(ops_buf.len() as isize),
-                0,
+                index_base as i32,
                 &mut result_ptr,
             )
         } < 0
@@ -370,10 +827,32 @@ impl Index {
     /// - `value` - encoded tuple in MsgPack Array format (`[field1, field2, ...]`)
     /// - `ops` - encoded operations in MsgPack array format, e.g. `[['=', field_id, value], ['!', 2, 'xxx']]`
     ///
+    /// **Note:** field numbers in `ops` are **zero-based** here (unlike Lua API where they start from 1).
+    /// Use [index.upsert_with_base()](#method.upsert_with_base) to choose numbering explicitly.
+    ///
     /// Returns a new tuple.
     ///
     /// See also: [index.update()](#method.update)
     pub fn upsert<T, Op>(&mut self, value: &T, ops: &Vec<Op>) -> Result<Option<Tuple>, Error>
+    where
+        T: AsTuple,
+        Op: AsTuple,
+    {
+        self.upsert_with_base(value, ops, IndexBase::Zero)
+    }
+
+    /// Execute an UPSERT request using explicitly specified numbering of fields in `ops`.
+    ///
+    /// Same as [index.upsert()](#method.upsert), but field numbers in `ops` are interpreted according to
+    /// `index_base`.
+    ///
+    /// See also: [IndexBase](enum.IndexBase.html)
+    pub fn upsert_with_base<T, Op>(
+        &mut self,
+        value: &T,
+        ops: &Vec<Op>,
+        index_base: IndexBase,
+    ) -> Result<Option<Tuple>, Error>
     where
         T: AsTuple,
         Op: AsTuple,
@@ -392,7 +871,7 @@ impl Index {
                 value_buf_ptr.offset(value_buf.len() as isize),
                 ops_buf_ptr,
                 ops_buf_ptr.offset(ops_buf.len() as isize),
-                0,
+                index_base as i32,
                 &mut result_ptr,
             )
         } < 0
@@ -453,9 +932,9 @@ impl Index {
     /// Returns a tuple or `None` if index is empty
     pub fn min<K>(&self, key: &K) -> Result<Option<Tuple>, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
-        let key_buf = key.serialize_as_tuple().unwrap();
+        let key_buf = key.serialize_as_key().unwrap();
         let key_buf_ptr = key_buf.as_ptr() as *const c_char;
         let mut result_ptr = null_mut::<ffi::BoxTuple>();
 
@@ -486,9 +965,9 @@ impl Index {
     /// Returns a tuple or `None` if index is empty
     pub fn max<K>(&self, key: &K) -> Result<Option<Tuple>, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
-        let key_buf = key.serialize_as_tuple().unwrap();
+        let key_buf = key.serialize_as_key().unwrap();
         let key_buf_ptr = key_buf.as_ptr() as *const c_char;
         let mut result_ptr = null_mut::<ffi::BoxTuple>();
 
@@ -518,9 +997,9 @@ impl Index {
     /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
     pub fn count<K>(&self, iterator_type: IteratorType, key: &K) -> Result<usize, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
-        let key_buf = key.serialize_as_tuple().unwrap();
+        let key_buf = key.serialize_as_key().unwrap();
         let key_buf_ptr = key_buf.as_ptr() as *const c_char;
 
         let result = unsafe {
@@ -557,31 +1036,448 @@ impl Index {
         };
         Tuple::from_raw_data(result_ptr, result_size)
     }
+
+    /// Same as [extract_key()](#method.extract_key), but returns an error if the tuple doesn't match the key
+    /// definition.
+    fn try_extract_key(&self, tuple: &Tuple) -> Result<Tuple, Error> {
+        let mut result_size: u32 = 0;
+        let result_ptr = unsafe {
+            ffi::box_tuple_extract_key(
+                tuple.clone().into_ptr(),
+                self.space_id,
+                self.index_id,
+                &mut result_size,
+            )
+        };
+        if result_ptr.is_null() {
+            return Err(TarantoolError::last().into());
+        }
+        Ok(Tuple::from_raw_data(result_ptr, result_size))
+    }
+}
+
+/// Looks up tuples of the space in an index by their keys, see [Index::verifier](struct.Index.html#method.verifier).
+pub(crate) struct IndexVerifier {
+    index: Index,
+    name: String,
+    key_fields: Vec<u32>,
+}
+
+impl IndexVerifier {
+    pub(crate) fn index_id(&self) -> u32 {
+        self.index.index_id
+    }
+
+    pub(crate) fn index_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if exactly this version of `tuple` is found in the index by its key.
+    pub(crate) fn contains(&self, tuple: &Tuple) -> Result<bool, Error> {
+        let mut key = project_fields(tuple.as_raw_bytes(), &self.key_fields)?;
+        let key = Tuple::from_raw_data(key.as_mut_ptr() as *mut c_char, key.len() as u32);
+        for found in self.index.select(IteratorType::Eq, &key)? {
+            if found.as_raw_bytes() == tuple.as_raw_bytes() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Bidirectional cursor over an index, see [Index::cursor](struct.Index.html#method.cursor).
+///
+/// The cursor remembers the key of the current tuple and reopens the index with `GT` or `LT` iterator on each step,
+/// so the index ought to be unique (of type `TREE`). Initially the cursor is positioned before the first tuple.
+///
+/// Being an [Iterator](https://doc.rust-lang.org/std/iter/trait.Iterator.html), it steps forward with `next()`, and
+/// steps back with [prev()](#method.prev).
+pub struct BiCursor {
+    index: Index,
+    key: Option<Tuple>,
+}
+
+impl BiCursor {
+    /// Moves the cursor to the tuple preceding the current one and returns it.
+    ///
+    /// Returns `None` (and stays at the current tuple) if there is no preceding tuple or the select fails, use
+    /// [try_prev()](#method.try_prev) to tell these cases apart.
+    pub fn prev(&mut self) -> Option<Tuple> {
+        self.try_prev().ok().flatten()
+    }
+
+    /// Same as [prev()](#method.prev), but reports select errors.
+    pub fn try_prev(&mut self) -> Result<Option<Tuple>, Error> {
+        let tuple = match &self.key {
+            None => return Ok(None),
+            Some(key) => self.index.select(IteratorType::LT, key)?.try_next()?,
+        };
+        Ok(self.move_to(tuple))
+    }
+
+    /// Moves the cursor to the tuple following the current one and returns it, reporting select errors.
+    ///
+    /// Returns `Ok(None)` (and stays at the current tuple) if there is no following tuple.
+    pub fn try_next(&mut self) -> Result<Option<Tuple>, Error> {
+        let tuple = match &self.key {
+            None => self.index.select(IteratorType::GE, &())?.try_next()?,
+            Some(key) => self.index.select(IteratorType::GT, key)?.try_next()?,
+        };
+        Ok(self.move_to(tuple))
+    }
+
+    /// Returns the key of the current tuple (`None` if the cursor is before the first tuple).
+    pub fn key(&self) -> Option<&Tuple> {
+        self.key.as_ref()
+    }
+
+    fn move_to(&mut self, tuple: Option<Tuple>) -> Option<Tuple> {
+        let tuple = tuple?;
+        self.key = Some(self.index.extract_key(tuple.clone()));
+        Some(tuple)
+    }
+}
+
+impl Iterator for BiCursor {
+    type Item = Tuple;
+
+    /// Moves the cursor to the tuple following the current one and returns it.
+    ///
+    /// Returns `None` (and stays at the current tuple) if there is no following tuple or the select fails, use
+    /// [try_next()](#method.try_next) to tell these cases apart.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next().ok().flatten()
+    }
+}
+
+fn required_field<T>(tuple: &Tuple, fieldno: u32) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    match tuple.field::<T>(fieldno)? {
+        Some(value) => Ok(value),
+        None => {
+            set_error!(
+                TarantoolErrorCode::NoSuchFieldNo,
+                "Field {} was not found in the tuple",
+                fieldno
+            );
+            Err(TarantoolError::last().into())
+        }
+    }
 }
 
 /// Index iterator. Can be used with `for` statement.
+///
+/// Being a regular [Iterator](https://doc.rust-lang.org/std/iter/trait.Iterator.html), it can be filtered during
+/// iteration. Decode only the fields the predicate needs with [Tuple::field](../tuple/struct.Tuple.html#method.field),
+/// so rejected tuples are never fully decoded:
+/// ```rust
+/// let rows: Vec<Row> = index
+///     .select(IteratorType::All, &())?
+///     .filter(|t| t.field::<i32>(3).ok().flatten() == Some(42))
+///     .map(|t| t.into_struct())
+///     .collect::<Result<_, _>>()?;
+/// ```
 pub struct IndexIterator {
     ptr: *mut ffi::BoxIterator,
     _key_data: TupleBuffer,
+    txn_id: Option<i64>,
+}
+
+impl IndexIterator {
+    /// Fetch the next tuple, reporting errors instead of ending the iteration.
+    ///
+    /// An iterator opened inside a transaction is bound to it: once the transaction is committed or rolled back,
+    /// the iterator may return stale data, so `TransactionError::Ended` is returned instead.
+    /// [next()](#method.next) ends the iteration in this case and logs the error.
+    ///
+    /// Only iterators opened inside a transaction pay for the check (requires Tarantool 2.6+ for `box_txn_id()`).
+    pub fn try_next(&mut self) -> Result<Option<Tuple>, Error> {
+        let result_ptr = self.next_ptr()?;
+        if result_ptr.is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(Tuple::from_ptr(result_ptr)))
+        }
+    }
+
+    fn next_ptr(&mut self) -> Result<*mut ffi::BoxTuple, Error> {
+        if self.txn_id.is_some() && self.txn_id != current_txn_id() {
+            return Err(TransactionError::Ended.into());
+        }
+
+        let mut result_ptr = null_mut::<ffi::BoxTuple>();
+        if unsafe { ffi::box_iterator_next(self.ptr, &mut result_ptr) } < 0 {
+            return Err(TarantoolError::last().into());
+        }
+        Ok(result_ptr)
+    }
+}
+
+impl IndexIterator {
+    /// Decode only the fields `fields` (zero-based, in the given order) of each tuple into `T`, see
+    /// [Tuple::project()](../tuple/struct.Tuple.html#method.project).
+    ///
+    /// Errors are yielded as items, the same way as in [IndexStream](struct.IndexStream.html).
+    pub fn project<T>(self, fields: &[u32]) -> IndexProjection<T> {
+        IndexProjection {
+            inner: self,
+            fields: fields.to_vec(),
+            is_failed: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Decode all the remaining tuples into `T` and sort them by the key extracted with `key_fn`, e.g. to order rows
+    /// by a field which is not covered by any index.
+    ///
+    /// Tuples are decoded directly from the tuple memory, the same way as in
+    /// [select_stream](struct.Index.html#method.select_stream). The sort is stable: rows with equal keys keep the
+    /// iteration order.
+    pub fn sorted_by<T, K, F>(self, key_fn: F) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned,
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        let stream = IndexStream {
+            inner: self,
+            is_failed: false,
+            _marker: PhantomData,
+        };
+        let mut rows = stream.collect::<Result<Vec<T>, Error>>()?;
+        rows.sort_by_key(key_fn);
+        Ok(rows)
+    }
 }
 
+/// Ends the iteration on any error. If the transaction the iterator was opened in has ended, the error is logged (see
+/// [try_next()](struct.IndexIterator.html#method.try_next)).
 impl Iterator for IndexIterator {
     type Item = Tuple;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut result_ptr = null_mut::<ffi::BoxTuple>();
-        if unsafe { ffi::box_iterator_next(self.ptr, &mut result_ptr) } < 0 {
+        match self.try_next() {
+            Ok(tuple) => tuple,
+            Err(err @ Error::Transaction(TransactionError::Ended)) => {
+                log::error!("{}", err);
+                None
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+/// Index iterator which knows the number of remaining tuples, see
+/// [select_counted](struct.Index.html#method.select_counted).
+pub struct CountedIndexIterator {
+    inner: IndexIterator,
+    remaining: usize,
+}
+
+impl Iterator for CountedIndexIterator {
+    type Item = Tuple;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
             return None;
         }
+        match self.inner.next() {
+            Some(tuple) => {
+                self.remaining -= 1;
+                Some(tuple)
+            }
+            None => {
+                self.remaining = 0;
+                None
+            }
+        }
+    }
 
-        if result_ptr.is_null() {
-            None
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for CountedIndexIterator {}
+
+/// Index iterator decoding tuples into `T`, see [select_stream](struct.Index.html#method.select_stream).
+///
+/// Errors (including `TransactionError::Ended`, see [IndexIterator::try_next](struct.IndexIterator.html#method.try_next))
+/// are yielded as items; iteration stops after the first iterator error.
+pub struct IndexStream<T> {
+    inner: IndexIterator,
+    is_failed: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Iterator for IndexStream<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_failed {
+            return None;
+        }
+
+        match self.inner.next_ptr() {
+            Ok(result_ptr) if result_ptr.is_null() => None,
+            // The tuple is not referenced: it is decoded before the next yield, so it can't be freed meanwhile
+            Ok(result_ptr) => Some(decode_fields(unsafe { raw_bytes(result_ptr) })),
+            Err(err) => {
+                self.is_failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Index iterator decoding some fields of each tuple into `T`, see
+/// [IndexIterator::project](struct.IndexIterator.html#method.project).
+pub struct IndexProjection<T> {
+    inner: IndexIterator,
+    fields: Vec<u32>,
+    is_failed: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Iterator for IndexProjection<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_failed {
+            return None;
+        }
+
+        match self.inner.next_ptr() {
+            Ok(result_ptr) if result_ptr.is_null() => None,
+            // The tuple is not referenced: it is decoded before the next yield, so it can't be freed meanwhile
+            Ok(result_ptr) => Some(
+                project_fields(unsafe { raw_bytes(result_ptr) }, &self.fields)
+                    .and_then(|data| decode_fields(&data[..])),
+            ),
+            Err(err) => {
+                self.is_failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Iterator over a range of an index, see [range](struct.Index.html#method.range).
+pub struct IndexRange {
+    inner: IndexIterator,
+    end: Bound<TupleBuffer>,
+    key_def: Option<KeyDef>,
+    is_done: bool,
+}
+
+impl IndexRange {
+    /// Fetch the next tuple within the range, reporting errors instead of ending the iteration
+    /// (see [IndexIterator::try_next](struct.IndexIterator.html#method.try_next)).
+    pub fn try_next(&mut self) -> Result<Option<Tuple>, Error> {
+        if self.is_done {
+            return Ok(None);
+        }
+
+        let tuple = match self.inner.try_next()? {
+            Some(tuple) => tuple,
+            None => {
+                self.is_done = true;
+                return Ok(None);
+            }
+        };
+        let is_in_range = match (&self.end, &self.key_def) {
+            (Bound::Included(key), Some(key_def)) => {
+                key_def.compare_with_key_buf(&tuple, key) != Ordering::Greater
+            }
+            (Bound::Excluded(key), Some(key_def)) => {
+                key_def.compare_with_key_buf(&tuple, key) == Ordering::Less
+            }
+            _ => true,
+        };
+        if is_in_range {
+            Ok(Some(tuple))
         } else {
-            Some(Tuple::from_ptr(result_ptr))
+            self.is_done = true;
+            Ok(None)
         }
     }
 }
 
+impl Iterator for IndexRange {
+    type Item = Tuple;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next().ok().flatten()
+    }
+}
+
+/// Parses index parts stored in `_index` system space.
+fn parse_key_parts(parts: Vec<JsonValue>) -> Result<Vec<KeyDefItem>, Error> {
+    let mut items = Vec::with_capacity(parts.len());
+    for part in parts {
+        // parts are stored either as `[field, type]` (old format) or as `{field = ..., type = ...}`
+        let (field_id, field_type) = match &part {
+            JsonValue::Array(part) => (part.get(0), part.get(1)),
+            part => (part.get("field"), part.get("type")),
+        };
+        let field_id = field_id.and_then(JsonValue::as_u64);
+        let field_type = field_type
+            .and_then(JsonValue::as_str)
+            .and_then(parse_field_type);
+        match (field_id, field_type) {
+            (Some(field_id), Some(field_type)) => items.push(KeyDefItem {
+                field_id: field_id as u32,
+                field_type,
+            }),
+            _ => {
+                set_error!(
+                    TarantoolErrorCode::WrongIndexParts,
+                    "unsupported index part {}",
+                    part
+                );
+                return Err(TarantoolError::last().into());
+            }
+        }
+    }
+    Ok(items)
+}
+
+fn parse_field_type(name: &str) -> Option<FieldType> {
+    Some(match name.to_lowercase().as_str() {
+        "any" => FieldType::Any,
+        "unsigned" | "uint" | "num" => FieldType::Unsigned,
+        "string" | "str" => FieldType::String,
+        "number" => FieldType::Number,
+        "double" => FieldType::Double,
+        "integer" | "int" => FieldType::Integer,
+        "boolean" | "bool" => FieldType::Boolean,
+        "varbinary" => FieldType::Varbinary,
+        "scalar" => FieldType::Scalar,
+        "decimal" => FieldType::Decimal,
+        "uuid" => FieldType::Uuid,
+        "array" => FieldType::Array,
+        "map" => FieldType::Map,
+        _ => return None,
+    })
+}
+
+/// Returns id of the transaction of the current fiber (if any).
+fn current_txn_id() -> Option<i64> {
+    if unsafe { ffi::box_txn() } {
+        Some(unsafe { ffi::box_txn_id() })
+    } else {
+        None
+    }
+}
+
 impl Drop for IndexIterator {
     fn drop(&mut self) {
         unsafe { ffi::box_iterator_free(self.ptr) };
@@ -26,6 +26,9 @@ use num_traits::{FromPrimitive, ToPrimitive};
 use crate::ffi::tarantool as ffi;
 
 /// [Log](https://docs.rs/log/latest/log/trait.Log.html) trait implementation. Wraps [say()](fn.say.html).
+///
+/// The source file and line of the log macro call are passed to `say`, so the Tarantool log shows where the record
+/// comes from. If the file is unknown, the module path is used instead.
 pub struct TarantoolLogger {}
 
 impl Log for TarantoolLogger {
@@ -37,7 +40,10 @@ impl Log for TarantoolLogger {
     fn log(&self, record: &Record) {
         say(
             record.level().into(),
-            record.file().unwrap_or_default(),
+            record
+                .file()
+                .or_else(|| record.module_path())
+                .unwrap_or_default(),
             record.line().unwrap_or(0) as i32,
             None,
             record.args().to_string().as_str(),
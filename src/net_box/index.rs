@@ -12,8 +12,8 @@ use super::Options;
 /// Remote index (a group of key values and pointers)
 pub struct RemoteIndex {
     conn_inner: Rc<ConnInner>,
-    space_id: u32,
-    index_id: u32,
+    pub(super) space_id: u32,
+    pub(super) index_id: u32,
 }
 
 impl RemoteIndex {
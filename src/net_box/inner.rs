@@ -5,13 +5,14 @@ use std::net::SocketAddr;
 use std::rc::{Rc, Weak};
 use std::time::Duration;
 
+use crate::clock::monotonic;
 use crate::coio::CoIOStream;
-use crate::error::Error;
+use crate::error::{Error, TarantoolErrorCode};
 use crate::fiber::{is_cancelled, set_cancellable, sleep, time, Cond, Fiber};
 use crate::net_box::stream::ConnStream;
 
-use super::options::{ConnOptions, ConnTriggers, Options};
-use super::protocol::{self, Header};
+use super::options::{ConnOptions, ConnTriggers, Options, RequestEvent};
+use super::protocol::{self, ConnFeatures, Header, RequestKind};
 use super::recv_queue::RecvQueue;
 use super::schema::ConnSchema;
 use super::send_queue::{self, SendQueue};
@@ -28,6 +29,8 @@ enum ConnState {
     Closed,
 }
 
+type Tracer = Rc<dyn Fn(RequestEvent)>;
+
 pub struct ConnInner {
     addrs: Vec<SocketAddr>,
     options: ConnOptions,
@@ -35,12 +38,17 @@ pub struct ConnInner {
     state_change_cond: Cond,
     schema: Rc<ConnSchema>,
     schema_version: Cell<Option<u32>>,
+    peer_version: Cell<(u32, u32, u32)>,
+    features: RefCell<ConnFeatures>,
     stream: RefCell<Option<ConnStream>>,
     send_queue: SendQueue,
     recv_queue: RecvQueue,
     send_fiber: RefCell<Fiber<'static, Rc<ConnInner>>>,
     recv_fiber: RefCell<Fiber<'static, Rc<ConnInner>>>,
+    keepalive_fiber: RefCell<Option<Fiber<'static, Rc<ConnInner>>>>,
+    last_request_ts: Cell<f64>,
     triggers: RefCell<Option<ConnTriggersWrapper>>,
+    tracer: RefCell<Option<Tracer>>,
     error: RefCell<Option<io::Error>>,
 }
 
@@ -58,12 +66,21 @@ impl ConnInner {
         let mut send_fiber = Fiber::new("_send_worker", &mut send_worker);
         send_fiber.set_joinable(true);
 
+        // init keepalive fiber (if required)
+        let keepalive_fiber = options.keepalive_interval.map(|_| {
+            let mut keepalive_fiber = Fiber::new("_keepalive_worker", &mut keepalive_worker);
+            keepalive_fiber.set_joinable(true);
+            keepalive_fiber
+        });
+
         // construct object
         let conn_inner = Rc::new(ConnInner {
             state: Cell::new(ConnState::Init),
             state_change_cond: Cond::new(),
             schema: ConnSchema::acquire(&addrs),
             schema_version: Cell::new(None),
+            peer_version: Cell::new((0, 0, 0)),
+            features: RefCell::new(ConnFeatures::default()),
             stream: RefCell::new(None),
             send_queue: SendQueue::new(
                 options.send_buffer_size,
@@ -73,7 +90,10 @@ impl ConnInner {
             recv_queue: RecvQueue::new(options.recv_buffer_size),
             send_fiber: RefCell::new(send_fiber),
             recv_fiber: RefCell::new(recv_fiber),
+            keepalive_fiber: RefCell::new(keepalive_fiber),
+            last_request_ts: Cell::new(time()),
             triggers: RefCell::new(None),
+            tracer: RefCell::new(None),
             error: RefCell::new(None),
             addrs,
             options,
@@ -90,6 +110,9 @@ impl ConnInner {
         // start send/recv fibers
         conn_inner.send_fiber.borrow_mut().start(conn_inner.clone());
         conn_inner.recv_fiber.borrow_mut().start(conn_inner.clone());
+        if let Some(keepalive_fiber) = conn_inner.keepalive_fiber.borrow_mut().as_mut() {
+            keepalive_fiber.start(conn_inner.clone());
+        }
 
         conn_inner
     }
@@ -134,6 +157,20 @@ impl ConnInner {
         Fp: FnOnce(&mut Cursor<Vec<u8>>, u64) -> Result<(), Error>,
         Fc: FnOnce(&mut Cursor<Vec<u8>>, &Header) -> Result<R, Error>,
     {
+        self.last_request_ts.set(time());
+
+        let default_timeout_options;
+        let options = match self.options.request_timeout {
+            Some(timeout) if options.timeout.is_none() => {
+                default_timeout_options = Options {
+                    timeout: Some(timeout),
+                    ..options.clone()
+                };
+                &default_timeout_options
+            }
+            _ => options,
+        };
+
         loop {
             let state = self.state.get();
             match state {
@@ -141,17 +178,50 @@ impl ConnInner {
                     self.init()?;
                 }
                 ConnState::Active => {
-                    return match self.send_queue.send(request_producer) {
-                        Ok(sync) => self
-                            .recv_queue
-                            .recv(sync, response_consumer, options)
-                            .and_then(|response| {
-                                self.schema_version
-                                    .set(Some(response.header.schema_version));
-                                Ok(response.payload)
-                            }),
-                        Err(err) => Err(self.handle_error(err.into()).err().unwrap()),
+                    // clone the tracer, so it may be replaced by the tracer itself
+                    let tracer = self.tracer.borrow().clone();
+                    if tracer.is_none() {
+                        return match self.send_queue.send(request_producer) {
+                            Ok(sync) => self.recv(sync, response_consumer, options),
+                            Err(err) => Err(self.handle_error(err).err().unwrap()),
+                        };
+                    }
+                    let tracer = tracer.unwrap();
+
+                    let kind = Cell::new(RequestKind::Other(0));
+                    let start_ts = monotonic();
+                    let sync = match self.send_queue.send(|buf, sync| {
+                        let request_offset = buf.position() as usize;
+                        request_producer(buf, sync)?;
+                        if let Some(request_kind) =
+                            protocol::decode_request_kind(&buf.get_ref()[request_offset..])
+                        {
+                            kind.set(request_kind);
+                        }
+                        Ok(())
+                    }) {
+                        Ok(sync) => sync,
+                        Err(err) => return Err(self.handle_error(err).err().unwrap()),
                     };
+                    let kind = kind.get();
+                    tracer(RequestEvent::Start { kind, sync });
+
+                    let result = self.recv(sync, response_consumer, options);
+                    let elapsed = Duration::from_secs_f64((monotonic() - start_ts).max(0.));
+                    tracer(match &result {
+                        Ok(_) => RequestEvent::Finish {
+                            kind,
+                            sync,
+                            elapsed,
+                        },
+                        Err(err) => RequestEvent::Error {
+                            kind,
+                            sync,
+                            elapsed,
+                            error: err.to_string(),
+                        },
+                    });
+                    return result;
                 }
                 ConnState::Error => self.disconnect(),
                 ConnState::ErrorReconnect => self.reconnect_or_fail()?,
@@ -165,6 +235,23 @@ impl ConnInner {
         }
     }
 
+    fn recv<Fc, R>(&self, sync: u64, response_consumer: Fc, options: &Options) -> Result<R, Error>
+    where
+        Fc: FnOnce(&mut Cursor<Vec<u8>>, &Header) -> Result<R, Error>,
+    {
+        self.recv_queue
+            .recv(sync, response_consumer, options)
+            .map(|response| {
+                self.schema_version
+                    .set(Some(response.header.schema_version));
+                response.payload
+            })
+    }
+
+    pub fn set_tracer(&self, tracer: Option<Tracer>) {
+        self.tracer.replace(tracer);
+    }
+
     pub fn lookup_space(&self, name: &str) -> Result<Option<u32>, Error> {
         self.refresh_schema()?;
         Ok(self.schema.lookup_space(name))
@@ -175,6 +262,16 @@ impl ConnInner {
         Ok(self.schema.lookup_index(name, space_id))
     }
 
+    pub fn peer_version(&self) -> Result<(u32, u32, u32), Error> {
+        self.wait_handshake()?;
+        Ok(self.peer_version.get())
+    }
+
+    pub fn features(&self) -> Result<ConnFeatures, Error> {
+        self.wait_handshake()?;
+        Ok(self.features.borrow().clone())
+    }
+
     pub fn close(&self) {
         let state = self.state.get();
         if matches!(state, ConnState::Connecting) || matches!(state, ConnState::Auth) {
@@ -186,11 +283,16 @@ impl ConnInner {
 
             let mut send_fiber = self.send_fiber.borrow_mut();
             send_fiber.cancel();
-            send_fiber.join();
+            let _ = send_fiber.join();
 
             let mut recv_fiber = self.recv_fiber.borrow_mut();
             recv_fiber.cancel();
-            recv_fiber.join();
+            let _ = recv_fiber.join();
+
+            if let Some(keepalive_fiber) = self.keepalive_fiber.borrow_mut().as_mut() {
+                keepalive_fiber.cancel();
+                let _ = keepalive_fiber.join();
+            }
         }
     }
 
@@ -217,12 +319,26 @@ impl ConnInner {
         };
 
         // receive greeting msg
-        let salt = protocol::decode_greeting(&mut stream)?;
+        let greeting = protocol::decode_greeting(&mut stream)?;
+        self.peer_version.set(greeting.version);
+
+        // negotiate protocol features
+        // (servers older than 2.10 reject `IPROTO_ID`: assume baseline features in that case)
+        let features = match self.handshake_request(&mut stream, protocol::encode_id) {
+            Ok(mut response) => protocol::decode_id(&mut response)?,
+            Err(Error::Remote(err))
+                if err.code() == TarantoolErrorCode::UnknownRequestType as u32 =>
+            {
+                ConnFeatures::default()
+            }
+            Err(err) => return Err(err),
+        };
+        self.features.replace(features);
 
         // auth if required
         if !self.options.user.is_empty() {
             self.update_state(ConnState::Auth);
-            self.auth(&mut stream, &salt)?;
+            self.auth(&mut stream, &greeting.salt)?;
         }
 
         // if ok: put stream to result + set state to active
@@ -241,12 +357,7 @@ impl ConnInner {
     }
 
     fn auth(&self, stream: &mut CoIOStream, salt: &Vec<u8>) -> Result<(), Error> {
-        let buf = Vec::new();
-        let mut cur = Cursor::new(buf);
-
-        // send auth request
-        let sync = self.send_queue.next_sync();
-        send_queue::write_to_buffer(&mut cur, sync, |buf, sync| {
+        self.handshake_request(stream, |buf, sync| {
             protocol::encode_auth(
                 buf,
                 self.options.user.as_str(),
@@ -255,7 +366,27 @@ impl ConnInner {
                 sync,
             )
         })?;
-        stream.write(cur.get_ref())?;
+        Ok(())
+    }
+
+    /// Send request and wait for response directly via `stream` (before send/recv workers take the stream over).
+    ///
+    /// Returns buffer positioned at the response body.
+    fn handshake_request<Fp>(
+        &self,
+        stream: &mut CoIOStream,
+        request_producer: Fp,
+    ) -> Result<Cursor<Vec<u8>>, Error>
+    where
+        Fp: FnOnce(&mut Cursor<Vec<u8>>, u64) -> Result<(), Error>,
+    {
+        let buf = Vec::new();
+        let mut cur = Cursor::new(buf);
+
+        // send request
+        let sync = self.send_queue.next_sync();
+        send_queue::write_to_buffer(&mut cur, sync, request_producer)?;
+        stream.write_all(cur.get_ref())?;
 
         // handle response
         let response_len = rmp::decode::read_u32(stream)?;
@@ -269,10 +400,18 @@ impl ConnInner {
 
         let header = protocol::decode_header(&mut cur)?;
         if header.status_code != 0 {
-            return Err(protocol::decode_error(stream)?.into());
+            return Err(protocol::decode_error(&mut cur, &header)?.into());
         }
 
-        Ok(())
+        Ok(cur)
+    }
+
+    fn wait_handshake(&self) -> Result<(), Error> {
+        if self.wait_connected(Some(self.options.connect_timeout))? {
+            Ok(())
+        } else {
+            Err(io::Error::from(io::ErrorKind::NotConnected).into())
+        }
     }
 
     fn refresh_schema(&self) -> Result<(), Error> {
@@ -308,13 +447,13 @@ impl ConnInner {
             return Ok(());
         }
 
-        match err {
-            Error::IO(err) => {
+        match err.into_io_error() {
+            Ok(err) => {
                 self.error.replace(Some(err));
                 self.update_state(ConnState::ErrorReconnect);
                 Ok(())
             }
-            err => {
+            Err(err) => {
                 self.update_state(ConnState::Error);
                 Err(err)
             }
@@ -435,3 +574,45 @@ fn recv_worker(conn: Box<Rc<ConnInner>>) -> i32 {
         }
     }
 }
+
+fn keepalive_worker(conn: Box<Rc<ConnInner>>) -> i32 {
+    set_cancellable(true);
+    let conn = *conn;
+    let interval = conn.options.keepalive_interval.unwrap();
+
+    loop {
+        if is_cancelled() {
+            return 0;
+        }
+
+        match conn.state.get() {
+            ConnState::Active => {
+                let idle_time = time() - conn.last_request_ts.get();
+                if idle_time < interval.as_secs_f64() {
+                    sleep(interval.as_secs_f64() - idle_time);
+                    continue;
+                }
+
+                let options = Options {
+                    timeout: Some(interval),
+                    ..Options::default()
+                };
+                if let Err(e) = conn.request(protocol::encode_ping, |_, _| Ok(()), &options) {
+                    if is_cancelled() {
+                        return 0;
+                    }
+                    if conn.is_connected() {
+                        let e = e
+                            .into_io_error()
+                            .unwrap_or_else(|_| io::Error::from(io::ErrorKind::ConnectionAborted));
+                        conn.handle_error(e.into()).unwrap();
+                    }
+                }
+            }
+            ConnState::Closed => return 0,
+            _ => {
+                conn.wait_state_changed(None);
+            }
+        }
+    }
+}
@@ -40,21 +40,25 @@
 #![cfg(feature = "net_box")]
 
 use core::time::Duration;
+use std::io;
 use std::net::ToSocketAddrs;
 use std::rc::Rc;
 
 pub use index::{RemoteIndex, RemoteIndexIterator};
 use inner::ConnInner;
-pub use options::{ConnOptions, ConnTriggers, Options};
-pub(crate) use protocol::ResponseError;
+pub use options::{ConnOptions, ConnTriggers, Options, RequestEvent};
+pub use pipeline::{Pipeline, PipelineResponses};
+pub use protocol::{ConnFeatures, RemoteError, RequestKind, ResponseError};
 pub use space::RemoteSpace;
 
+use crate::clock::Deadline;
 use crate::error::Error;
 use crate::tuple::{AsTuple, Tuple};
 
 mod index;
 mod inner;
 mod options;
+mod pipeline;
 mod protocol;
 mod recv_queue;
 mod schema;
@@ -102,6 +106,34 @@ impl Conn {
         self.inner.is_connected()
     }
 
+    /// Returns version of remote Tarantool server as `(major, minor, patch)` (waits for connection if required).
+    pub fn peer_version(&self) -> Result<(u32, u32, u32), Error> {
+        self.inner.peer_version()
+    }
+
+    /// Returns protocol features supported by remote server (waits for connection if required).
+    ///
+    /// Use it to enable optional functionality (e.g. streams or watchers) only when server supports it.
+    pub fn features(&self) -> Result<ConnFeatures, Error> {
+        self.inner.features()
+    }
+
+    /// Set a tracer called on each request [lifecycle event](enum.RequestEvent.html): start, response and error.
+    ///
+    /// Useful to feed request spans into a tracing backend without wrapping every call. The tracer is shared by all
+    /// handles of the connection and replaces the previous one.
+    pub fn set_tracer<F>(&self, tracer: F)
+    where
+        F: Fn(RequestEvent) + 'static,
+    {
+        self.inner.set_tracer(Some(Rc::new(tracer)))
+    }
+
+    /// Remove the tracer set by [set_tracer()](#method.set_tracer).
+    pub fn remove_tracer(&self) {
+        self.inner.set_tracer(None)
+    }
+
     /// Close a connection.
     pub fn close(&self) {
         self.inner.close()
@@ -137,6 +169,51 @@ impl Conn {
         )
     }
 
+    /// Same as [call()](#method.call), but returns the results as raw MessagePack (an array of values returned by the
+    /// function) without decoding them, e.g. to forward them verbatim in a proxy.
+    pub fn call_raw<T>(
+        &self,
+        function_name: &str,
+        args: &T,
+        options: &Options,
+    ) -> Result<Vec<u8>, Error>
+    where
+        T: AsTuple,
+    {
+        self.inner.request(
+            |buf, sync| protocol::encode_call(buf, sync, function_name, args),
+            protocol::decode_call_raw,
+            options,
+        )
+    }
+
+    /// Same as [call()](#method.call), but the call must complete before `deadline`.
+    ///
+    /// Use the same deadline for several calls to fit all of them into one time budget. If the deadline has already
+    /// passed, fails with `TimedOut` IO error without sending the request.
+    pub fn call_with_deadline<T>(
+        &self,
+        function_name: &str,
+        args: &T,
+        deadline: Deadline,
+    ) -> Result<Option<Tuple>, Error>
+    where
+        T: AsTuple,
+    {
+        if deadline.is_expired() {
+            return Err(io::Error::from(io::ErrorKind::TimedOut).into());
+        }
+
+        self.call(
+            function_name,
+            args,
+            &Options {
+                timeout: Some(deadline.remaining()),
+                ..Options::default()
+            },
+        )
+    }
+
     /// Evaluates and executes the expression in Lua-string, which may be any statement or series of statements.
     ///
     /// An execute privilege is required; if the user does not have it, an administrator may grant it with
@@ -160,6 +237,77 @@ impl Conn {
         )
     }
 
+    /// Same as [eval()](#method.eval), but the expression is loaded as a chunk named `name`, so the errors raised by
+    /// it (and the syntax errors) refer to this name instead of an anonymous chunk, e.g. `"migrations:3: oops"`.
+    pub fn eval_named<T>(
+        &self,
+        name: &str,
+        expression: &str,
+        args: &T,
+        options: &Options,
+    ) -> Result<Option<Tuple>, Error>
+    where
+        T: AsTuple,
+    {
+        self.inner.request(
+            |buf, sync| protocol::encode_eval_named(buf, sync, name, expression, args),
+            protocol::decode_call,
+            options,
+        )
+    }
+
+    /// Register a persistent Lua function on the remote server, so it can be called by name with [call()](#method.call).
+    ///
+    /// `lua_body` is the source of a Lua function, e.g. `"function(a, b) return a + b end"`. If a function with
+    /// this name already exists, it is replaced: the old function is dropped and the new one is created in a single
+    /// transaction, and the privileges granted on the old function are granted on the new one. Wraps
+    /// [box.schema.func.create()](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_schema/func_create/),
+    /// so the user needs the privilege to create functions.
+    pub fn register_function(
+        &self,
+        name: &str,
+        lua_body: &str,
+        options: &Options,
+    ) -> Result<(), Error> {
+        self.eval(
+            "local name, body = ...
+            box.atomic(function()
+                local privs = {}
+                local old = box.space._func.index.name:get(name)
+                if old ~= nil then
+                    privs = box.space._priv.index.object:select({'function', old.id})
+                    box.schema.func.drop(name)
+                end
+                box.schema.func.create(name, {body = body})
+                local id = box.space._func.index.name:get(name).id
+                for _, priv in ipairs(privs) do
+                    box.space._priv:replace(priv:update({{'=', 4, id}}))
+                end
+            end)",
+            &(name, lua_body),
+            options,
+        )?;
+        Ok(())
+    }
+
+    /// Drop a function registered with [register_function()](#method.register_function).
+    ///
+    /// Does nothing if the function doesn't exist.
+    pub fn drop_function(&self, name: &str, options: &Options) -> Result<(), Error> {
+        self.eval(
+            "box.schema.func.drop(..., {if_exists = true})",
+            &(name,),
+            options,
+        )?;
+        Ok(())
+    }
+
+    /// Create a [Pipeline](struct.Pipeline.html): a batch of requests which are sent without waiting for each
+    /// response.
+    pub fn pipeline(&self) -> Pipeline {
+        Pipeline::new(self.inner.clone())
+    }
+
     /// Search space by name on remote server
     pub fn space(&self, name: &str) -> Result<Option<RemoteSpace>, Error> {
         Ok(self
@@ -1,7 +1,7 @@
 use bitflags::_core::time::Duration;
 
 use crate::error::Error;
-use crate::net_box::Conn;
+use crate::net_box::{Conn, RequestKind};
 
 /// Most [Conn](struct.Conn.html) methods allows to pass an `options` argument
 ///
@@ -64,8 +64,17 @@ pub struct ConnOptions {
     pub reconnect_after: Duration,
 
     /// Duration to wait before returning “error: Connection timed out”.
+    ///
+    /// Limits only establishing the connection (including the handshake), see `request_timeout` for requests.
     pub connect_timeout: Duration,
 
+    /// Default timeout of a request, used when the `timeout` of request [Options](struct.Options.html) is `None`.
+    ///
+    /// Independent from `connect_timeout`: a slow request doesn't affect the connection, which remains usable after
+    /// the request timed out.
+    /// Default: `None` (wait for the response forever)
+    pub request_timeout: Option<Duration>,
+
     /// Send buffer flush interval enforced in case of intensive requests stream.
     ///
     /// Guarantied to be maximum while requests are going.
@@ -88,6 +97,12 @@ pub struct ConnOptions {
     ///
     /// Default: 65536
     pub recv_buffer_size: usize,
+
+    /// If set, the connection is pinged when no requests were made for this interval (keeps idle connections alive
+    /// through NAT and proxies). A failed ping is treated as a connection drop (see `reconnect_after`).
+    ///
+    /// Default: `None`
+    pub keepalive_interval: Option<Duration>,
 }
 
 impl Default for ConnOptions {
@@ -97,14 +112,38 @@ impl Default for ConnOptions {
             password: "".to_string(),
             reconnect_after: Default::default(),
             connect_timeout: Default::default(),
+            request_timeout: None,
             send_buffer_flush_interval: Duration::from_millis(10),
             send_buffer_limit: 64000,
             send_buffer_size: 65536,
             recv_buffer_size: 65536,
+            keepalive_interval: None,
         }
     }
 }
 
+/// An event of a request lifecycle, passed to the tracer (see [Conn::set_tracer()](struct.Conn.html#method.set_tracer)).
+///
+/// Every `Start` is followed by either `Finish` or `Error` with the same `sync`.
+#[derive(Debug, Clone)]
+pub enum RequestEvent {
+    /// The request is queued for sending.
+    Start { kind: RequestKind, sync: u64 },
+    /// The response is received. `elapsed` is measured from the `Start` event.
+    Finish {
+        kind: RequestKind,
+        sync: u64,
+        elapsed: Duration,
+    },
+    /// The request failed (e.g. the server responded with an error, or the request timed out).
+    Error {
+        kind: RequestKind,
+        sync: u64,
+        elapsed: Duration,
+        error: String,
+    },
+}
+
 /// Provides triggers for connect, disconnect and schema reload events.
 pub trait ConnTriggers {
     /// Defines a trigger for execution when a new connection is established, and authentication and schema fetch are
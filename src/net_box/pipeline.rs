@@ -0,0 +1,196 @@
+use std::cell::RefCell;
+use std::io::{self, Cursor};
+use std::rc::Rc;
+
+use crate::error::Error;
+use crate::fiber::Fiber;
+use crate::index::IteratorType;
+use crate::tuple::{AsTuple, Tuple};
+
+use super::index::RemoteIndex;
+use super::inner::ConnInner;
+use super::options::Options;
+use super::protocol;
+
+type RequestProducer = Box<dyn FnOnce(&mut Cursor<Vec<u8>>, u64) -> Result<(), Error>>;
+type RequestCallback = Box<dyn FnMut(Box<()>) -> i32>;
+type ResponseSlots = Rc<RefCell<Vec<Option<Result<Option<Tuple>, Error>>>>>;
+
+/// Batch of requests sent back-to-back without waiting for responses
+/// (see [conn.pipeline()](struct.Conn.html#method.pipeline)).
+///
+/// The arguments of the requests are encoded when the requests are enqueued, so the pipeline doesn't borrow them.
+///
+/// Example:
+/// ```rust
+/// let mut pipeline = conn.pipeline();
+/// pipeline
+///     .call("func_1", &(1,))
+///     .eval("return ...", &(2,));
+/// let results = pipeline.send(&Options::default()).wait_all();
+/// ```
+pub struct Pipeline {
+    conn_inner: Rc<ConnInner>,
+    requests: Vec<RequestProducer>,
+}
+
+impl Pipeline {
+    pub(crate) fn new(conn_inner: Rc<ConnInner>) -> Self {
+        Pipeline {
+            conn_inner,
+            requests: vec![],
+        }
+    }
+
+    /// Enqueue a remote stored procedure call (see [conn.call()](struct.Conn.html#method.call)).
+    pub fn call<T>(&mut self, function_name: &str, args: &T) -> &mut Self
+    where
+        T: AsTuple,
+    {
+        let function_name = function_name.to_string();
+        let args = Tuple::from_struct(args);
+        self.requests.push(Box::new(move |buf, sync| {
+            protocol::encode_call(buf, sync, &function_name, &args?)
+        }));
+        self
+    }
+
+    /// Enqueue a Lua expression evaluation (see [conn.eval()](struct.Conn.html#method.eval)).
+    pub fn eval<T>(&mut self, expression: &str, args: &T) -> &mut Self
+    where
+        T: AsTuple,
+    {
+        let expression = expression.to_string();
+        let args = Tuple::from_struct(args);
+        self.requests.push(Box::new(move |buf, sync| {
+            protocol::encode_eval(buf, sync, &expression, &args?)
+        }));
+        self
+    }
+
+    /// Enqueue a select from the remote `index` (see [index.select()](struct.RemoteIndex.html#method.select)).
+    ///
+    /// The result of this request is a tuple containing the array of selected tuples.
+    pub fn select<K>(
+        &mut self,
+        index: &RemoteIndex,
+        iterator_type: IteratorType,
+        key: &K,
+    ) -> &mut Self
+    where
+        K: AsTuple,
+    {
+        let space_id = index.space_id;
+        let index_id = index.index_id;
+        let key = Tuple::from_struct(key);
+        self.requests.push(Box::new(move |buf, sync| {
+            protocol::encode_select(
+                buf,
+                sync,
+                space_id,
+                index_id,
+                u32::MAX,
+                0,
+                iterator_type,
+                &key?,
+            )
+        }));
+        self
+    }
+
+    /// Returns the number of enqueued requests.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Returns `true` if no requests were enqueued.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Send all enqueued requests.
+    ///
+    /// Each request gets its own sync id and is awaited by its own fiber, so the requests are written to the socket
+    /// together and processed by the server concurrently.
+    ///
+    /// - `options` – the supported option is `timeout` (applied to each request)
+    pub fn send(self, options: &Options) -> PipelineResponses {
+        let slots: ResponseSlots = Rc::new(RefCell::new(
+            (0..self.requests.len()).map(|_| None).collect(),
+        ));
+
+        let mut callbacks: Vec<RequestCallback> = Vec::with_capacity(self.requests.len());
+        for (index, request) in self.requests.into_iter().enumerate() {
+            let conn_inner = self.conn_inner.clone();
+            let options = options.clone();
+            let slots = slots.clone();
+            let mut request = Some(request);
+            callbacks.push(Box::new(move |_| {
+                let request = request.take().unwrap();
+                let result = conn_inner.request(request, protocol::decode_call, &options);
+                slots.borrow_mut()[index] = Some(result);
+                0
+            }));
+        }
+
+        let mut fibers = Vec::with_capacity(callbacks.len());
+        for callback in callbacks.iter_mut() {
+            // `callbacks` is not modified after this point, so the addresses of its items stay the same while
+            // `PipelineResponses` is moved around
+            let mut fiber = Fiber::new("_pipeline_worker", callback);
+            fiber.set_joinable(true);
+            fiber.start(());
+            fibers.push(fiber);
+        }
+
+        PipelineResponses {
+            fibers,
+            _callbacks: callbacks,
+            slots,
+        }
+    }
+}
+
+/// Pending responses of the [Pipeline](struct.Pipeline.html) requests.
+///
+/// Dropping it waits for all responses.
+pub struct PipelineResponses {
+    fibers: Vec<Fiber<'static, ()>>,
+    _callbacks: Vec<RequestCallback>,
+    slots: ResponseSlots,
+}
+
+impl PipelineResponses {
+    /// Wait for all responses. Yields.
+    ///
+    /// Returns results in the order the requests were enqueued. Each result is the `DATA` field of the response
+    /// (`None` if the response has no data). If the fiber awaiting a request panicked, the result is the error of
+    /// that fiber.
+    pub fn wait_all(mut self) -> Vec<Result<Option<Tuple>, Error>> {
+        let joined = self.join();
+        let results = self
+            .slots
+            .borrow_mut()
+            .drain(..)
+            .zip(joined)
+            .map(|(slot, joined)| match slot {
+                Some(result) => result,
+                None => Err(joined.err().unwrap_or_else(|| {
+                    io::Error::new(io::ErrorKind::Other, "pipeline request was not completed")
+                        .into()
+                })),
+            })
+            .collect();
+        results
+    }
+
+    fn join(&mut self) -> Vec<Result<i32, Error>> {
+        self.fibers.drain(..).map(|fiber| fiber.join()).collect()
+    }
+}
+
+impl Drop for PipelineResponses {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
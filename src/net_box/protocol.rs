@@ -1,15 +1,15 @@
 use core::str::from_utf8;
 use std::cmp::min;
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::io::{self, Cursor, Read, Seek, Write};
 use std::os::raw::c_char;
 
-use byteorder::{BigEndian, ReadBytesExt};
 use sha1::{Digest, Sha1};
 
 use crate::error::Error;
 use crate::index::IteratorType;
-use crate::tuple::{AsTuple, Tuple};
+use crate::tuple::{ensure_array, skip_msgpack, AsTuple, Tuple};
 
 const REQUEST_TYPE: u8 = 0x00;
 const SYNC: u8 = 0x01;
@@ -31,6 +31,29 @@ const OPS: u8 = 0x28;
 
 const DATA: u8 = 0x30;
 const ERROR: u8 = 0x31;
+const ERROR_EXT: u8 = 0x52;
+
+/// Keys of `MP_ERROR` MsgPack extension
+const MP_ERROR_STACK: u8 = 0x00;
+const MP_ERROR_TYPE: u8 = 0x00;
+const MP_ERROR_MESSAGE: u8 = 0x03;
+const MP_ERROR_CODE: u8 = 0x05;
+const MP_ERROR_FIELDS: u8 = 0x06;
+
+/// Error code is the status code of a response without this bit
+const ERROR_STATUS_FLAG: u32 = 0x8000;
+
+const VERSION: u8 = 0x54;
+const FEATURES: u8 = 0x55;
+
+/// Version of binary protocol supported by this connector
+const PROTOCOL_VERSION: u32 = 3;
+
+const FEATURE_STREAMS: u32 = 0;
+const FEATURE_TRANSACTIONS: u32 = 1;
+const FEATURE_ERROR_EXTENSION: u32 = 2;
+const FEATURE_WATCHERS: u32 = 3;
+const FEATURE_PAGINATION: u32 = 4;
 
 enum IProtoType {
     Select = 1,
@@ -43,6 +66,58 @@ enum IProtoType {
     Upsert = 9,
     Call = 10,
     Ping = 64,
+    Id = 73,
+}
+
+/// Kind of a request (IPROTO request type), see [RequestEvent](enum.RequestEvent.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RequestKind {
+    Select,
+    Insert,
+    Replace,
+    Update,
+    Delete,
+    Auth,
+    Eval,
+    Upsert,
+    Call,
+    Ping,
+    Id,
+    /// Request type code which is not known to the connector.
+    Other(u8),
+}
+
+impl From<u8> for RequestKind {
+    fn from(code: u8) -> Self {
+        match code {
+            1 => RequestKind::Select,
+            2 => RequestKind::Insert,
+            3 => RequestKind::Replace,
+            4 => RequestKind::Update,
+            5 => RequestKind::Delete,
+            7 => RequestKind::Auth,
+            8 => RequestKind::Eval,
+            9 => RequestKind::Upsert,
+            10 => RequestKind::Call,
+            64 => RequestKind::Ping,
+            73 => RequestKind::Id,
+            code => RequestKind::Other(code),
+        }
+    }
+}
+
+/// Reads the request type from the header of an encoded request.
+pub fn decode_request_kind(request: &[u8]) -> Option<RequestKind> {
+    let mut cur = Cursor::new(request);
+    let len = rmp::decode::read_map_len(&mut cur).ok()?;
+    for _ in 0..len {
+        let key: u8 = rmp::decode::read_int(&mut cur).ok()?;
+        let value: u64 = rmp::decode::read_int(&mut cur).ok()?;
+        if key == REQUEST_TYPE {
+            return Some(RequestKind::from(value as u8));
+        }
+    }
+    None
 }
 
 fn encode_header(
@@ -112,6 +187,16 @@ pub fn encode_ping(stream: &mut impl Write, sync: u64) -> Result<(), Error> {
     Ok(())
 }
 
+pub fn encode_id(stream: &mut impl Write, sync: u64) -> Result<(), Error> {
+    encode_header(stream, sync, IProtoType::Id)?;
+    rmp::encode::write_map_len(stream, 2)?;
+    rmp::encode::write_pfix(stream, VERSION)?;
+    rmp::encode::write_uint(stream, PROTOCOL_VERSION as u64)?;
+    rmp::encode::write_pfix(stream, FEATURES)?;
+    rmp::encode::write_array_len(stream, 0)?;
+    Ok(())
+}
+
 pub fn encode_call<T>(
     stream: &mut impl Write,
     sync: u64,
@@ -148,6 +233,46 @@ where
     Ok(())
 }
 
+/// Loads the expression passed as the first argument as a chunk named by the second argument, then calls it with the
+/// rest of the arguments.
+const EVAL_NAMED: &str = "local expression, name = ...
+local chunk, err = loadstring(expression, '=' .. name)
+if chunk == nil then error(err, 0) end
+return chunk(select(3, ...))";
+
+pub fn encode_eval_named<T>(
+    stream: &mut impl Write,
+    sync: u64,
+    name: &str,
+    expression: &str,
+    args: &T,
+) -> Result<(), Error>
+where
+    T: AsTuple,
+{
+    let args = rmp_serde::to_vec(args)?;
+    let (args_len, args_data) = if args == [0xc0] {
+        // `()` is encoded as nil
+        (0, &args[..0])
+    } else {
+        ensure_array(&args)?;
+        let mut cur = Cursor::new(&args[..]);
+        let len = rmp::decode::read_array_len(&mut cur)?;
+        (len, &args[cur.position() as usize..])
+    };
+
+    encode_header(stream, sync, IProtoType::Eval)?;
+    rmp::encode::write_map_len(stream, 2)?;
+    rmp::encode::write_pfix(stream, EXPR)?;
+    rmp::encode::write_str(stream, EVAL_NAMED)?;
+    rmp::encode::write_pfix(stream, TUPLE)?;
+    rmp::encode::write_array_len(stream, args_len + 2)?;
+    rmp::encode::write_str(stream, expression)?;
+    rmp::encode::write_str(stream, name)?;
+    stream.write_all(args_data)?;
+    Ok(())
+}
+
 pub fn encode_select<K>(
     stream: &mut impl Write,
     sync: u64,
@@ -324,31 +449,126 @@ pub fn decode_header(stream: &mut (impl Read + Seek)) -> Result<Header, Error> {
     })
 }
 
-pub fn decode_error(stream: &mut impl Read) -> Result<ResponseError, Error> {
+pub fn decode_error(buffer: &mut Cursor<Vec<u8>>, header: &Header) -> Result<ResponseError, Error> {
     let mut message: Option<String> = None;
+    let mut stack = Vec::new();
 
-    let map_len = rmp::decode::read_map_len(stream)?;
+    let map_len = rmp::decode::read_map_len(buffer)?;
     for _ in 0..map_len {
-        if rmp::decode::read_pfix(stream)? == ERROR {
-            let str_len = rmp::decode::read_str_len(stream)? as usize;
-            let mut str_buf = vec![0u8; str_len];
-            stream.read_exact(&mut str_buf)?;
-            message = Some(from_utf8(&mut str_buf)?.to_string());
+        match rmp::decode::read_pfix(buffer)? {
+            ERROR => message = Some(decode_string(buffer)?),
+            ERROR_EXT => stack = decode_error_stack(buffer)?,
+            _ => skip_msgpack(buffer)?,
         }
     }
 
+    // the stack goes from the error itself to its first cause
+    let error = stack.into_iter().rev().fold(None, |prev, mut error| {
+        error.prev = prev.map(Box::new);
+        Some(error)
+    });
+
     Ok(ResponseError {
+        code: header.status_code & !ERROR_STATUS_FLAG,
         message: message.ok_or(io::Error::from(io::ErrorKind::InvalidData))?,
+        error,
     })
 }
 
-pub fn decode_greeting(stream: &mut impl Read) -> Result<Vec<u8>, Error> {
+fn decode_error_stack(buffer: &mut Cursor<Vec<u8>>) -> Result<Vec<RemoteError>, Error> {
+    let mut stack = Vec::new();
+    let map_len = rmp::decode::read_map_len(buffer)?;
+    for _ in 0..map_len {
+        if rmp::decode::read_pfix(buffer)? != MP_ERROR_STACK {
+            skip_msgpack(buffer)?;
+            continue;
+        }
+
+        let stack_len = rmp::decode::read_array_len(buffer)?;
+        for _ in 0..stack_len {
+            let mut error = RemoteError::default();
+            let error_map_len = rmp::decode::read_map_len(buffer)?;
+            for _ in 0..error_map_len {
+                match rmp::decode::read_pfix(buffer)? {
+                    MP_ERROR_TYPE => error.error_type = decode_string(buffer)?,
+                    MP_ERROR_MESSAGE => error.message = decode_string(buffer)?,
+                    MP_ERROR_CODE => error.code = rmp::decode::read_int(buffer)?,
+                    MP_ERROR_FIELDS => error.fields = rmp_serde::from_read(&mut *buffer)?,
+                    _ => skip_msgpack(buffer)?,
+                }
+            }
+            stack.push(error);
+        }
+    }
+    Ok(stack)
+}
+
+fn decode_string(stream: &mut impl Read) -> Result<String, Error> {
+    let str_len = rmp::decode::read_str_len(stream)? as usize;
+    let mut str_buf = vec![0u8; str_len];
+    stream.read_exact(&mut str_buf)?;
+    Ok(from_utf8(&str_buf)?.to_string())
+}
+
+pub struct Greeting {
+    pub version: (u32, u32, u32),
+    pub salt: Vec<u8>,
+}
+
+pub fn decode_greeting(stream: &mut impl Read) -> Result<Greeting, Error> {
     let mut buf = Vec::with_capacity(128);
     buf.resize(128, 0);
 
     stream.read_exact(&mut *buf)?;
     let salt = base64::decode(&buf[64..108]).unwrap();
-    Ok(salt)
+
+    // first line looks like: "Tarantool 2.10.0-beta1-0-g7da4b1438 (Binary) <uuid>"
+    let version = from_utf8(&buf[0..64])?
+        .split_whitespace()
+        .nth(1)
+        .map(parse_version)
+        .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+
+    Ok(Greeting { version, salt })
+}
+
+fn parse_version(version: &str) -> (u32, u32, u32) {
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+pub fn decode_id(buffer: &mut Cursor<Vec<u8>>) -> Result<ConnFeatures, Error> {
+    let mut features = ConnFeatures::default();
+    let payload_len = rmp::decode::read_map_len(buffer)?;
+    for _ in 0..payload_len {
+        let key = rmp::decode::read_pfix(buffer)?;
+        match key {
+            VERSION => features.protocol_version = rmp::decode::read_int(buffer)?,
+            FEATURES => {
+                let features_count = rmp::decode::read_array_len(buffer)?;
+                for _ in 0..features_count {
+                    match rmp::decode::read_int(buffer)? {
+                        FEATURE_STREAMS => features.streams = true,
+                        FEATURE_TRANSACTIONS => features.transactions = true,
+                        FEATURE_ERROR_EXTENSION => features.error_extension = true,
+                        FEATURE_WATCHERS => features.watchers = true,
+                        FEATURE_PAGINATION => features.pagination = true,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {
+                skip_msgpack(buffer)?;
+            }
+        }
+    }
+    Ok(features)
 }
 
 pub fn decode_call(buffer: &mut Cursor<Vec<u8>>, _: &Header) -> Result<Option<Tuple>, Error> {
@@ -367,6 +587,21 @@ pub fn decode_call(buffer: &mut Cursor<Vec<u8>>, _: &Header) -> Result<Option<Tu
     Ok(None)
 }
 
+/// Returns the MsgPack array of call results as is (an empty array if the response has no data).
+pub fn decode_call_raw(buffer: &mut Cursor<Vec<u8>>, _: &Header) -> Result<Vec<u8>, Error> {
+    let payload_len = rmp::decode::read_map_len(buffer)?;
+    for _ in 0..payload_len {
+        let key = rmp::decode::read_pfix(buffer)?;
+        let start = buffer.position() as usize;
+        skip_msgpack(buffer)?;
+        if key == DATA {
+            let end = buffer.position() as usize;
+            return Ok(buffer.get_ref()[start..end].to_vec());
+        }
+    }
+    Ok(vec![0x90])
+}
+
 pub fn decode_multiple_rows(
     buffer: &mut Cursor<Vec<u8>>,
     limit: Option<usize>,
@@ -422,6 +657,7 @@ pub fn decode_tuple(buffer: &mut Cursor<Vec<u8>>) -> Result<Tuple, Error> {
     skip_msgpack(buffer)?;
     let payload_len = buffer.position() - payload_offset;
     let buf = buffer.get_mut();
+    ensure_array(&buf[payload_offset as usize..(payload_offset + payload_len) as usize])?;
     unsafe {
         Ok(Tuple::from_raw_data(
             buf.as_slice().as_ptr().add(payload_offset as usize) as *mut c_char,
@@ -430,110 +666,75 @@ pub fn decode_tuple(buffer: &mut Cursor<Vec<u8>>) -> Result<Tuple, Error> {
     }
 }
 
-fn skip_msgpack(cur: &mut (impl Read + Seek)) -> Result<(), Error> {
-    use rmp::Marker;
+/// Protocol features supported by remote server (negotiated on connect via `IPROTO_ID` request)
+///
+/// If the server doesn't support `IPROTO_ID` (Tarantool older than 2.10), the default value is used: protocol
+/// version `0` and no optional features.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConnFeatures {
+    /// Version of binary protocol supported by server
+    pub protocol_version: u32,
 
-    match rmp::decode::read_marker(cur)? {
-        Marker::FixPos(_) | Marker::FixNeg(_) | Marker::Null | Marker::True | Marker::False => {}
-        Marker::U8 | Marker::I8 => {
-            cur.seek(SeekFrom::Current(1))?;
-        }
-        Marker::U16 | Marker::I16 => {
-            cur.seek(SeekFrom::Current(2))?;
-        }
-        Marker::U32 | Marker::I32 | Marker::F32 => {
-            cur.seek(SeekFrom::Current(4))?;
-        }
-        Marker::U64 | Marker::I64 | Marker::F64 => {
-            cur.seek(SeekFrom::Current(8))?;
-        }
-        Marker::FixStr(len) => {
-            cur.seek(SeekFrom::Current(len as i64))?;
-        }
-        Marker::Str8 | Marker::Bin8 => {
-            let len = cur.read_u8()?;
-            cur.seek(SeekFrom::Current(len as i64))?;
-        }
-        Marker::Str16 | Marker::Bin16 => {
-            let len = cur.read_u16::<BigEndian>()?;
-            cur.seek(SeekFrom::Current(len as i64))?;
-        }
-        Marker::Str32 | Marker::Bin32 => {
-            let len = cur.read_u32::<BigEndian>()?;
-            cur.seek(SeekFrom::Current(len as i64))?;
-        }
-        Marker::FixArray(len) => {
-            for _ in 0..len {
-                skip_msgpack(cur)?;
-            }
-        }
-        Marker::Array16 => {
-            let len = cur.read_u16::<BigEndian>()?;
-            for _ in 0..len {
-                skip_msgpack(cur)?;
-            }
-        }
-        Marker::Array32 => {
-            let len = cur.read_u32::<BigEndian>()?;
-            for _ in 0..len {
-                skip_msgpack(cur)?;
-            }
-        }
-        Marker::FixMap(len) => {
-            let len = len * 2;
-            for _ in 0..len {
-                skip_msgpack(cur)?;
-            }
-        }
-        Marker::Map16 => {
-            let len = cur.read_u16::<BigEndian>()? * 2;
-            for _ in 0..len {
-                skip_msgpack(cur)?;
-            }
-        }
-        Marker::Map32 => {
-            let len = cur.read_u32::<BigEndian>()? * 2;
-            for _ in 0..len {
-                skip_msgpack(cur)?;
-            }
-        }
-        Marker::FixExt1 => {
-            cur.seek(SeekFrom::Current(2))?;
-        }
-        Marker::FixExt2 => {
-            cur.seek(SeekFrom::Current(3))?;
-        }
-        Marker::FixExt4 => {
-            cur.seek(SeekFrom::Current(5))?;
-        }
-        Marker::FixExt8 => {
-            cur.seek(SeekFrom::Current(9))?;
-        }
-        Marker::FixExt16 => {
-            cur.seek(SeekFrom::Current(17))?;
-        }
-        Marker::Ext8 => {
-            let len = cur.read_u8()?;
-            cur.seek(SeekFrom::Current(len as i64 + 1))?;
-        }
-        Marker::Ext16 => {
-            let len = cur.read_u16::<BigEndian>()?;
-            cur.seek(SeekFrom::Current(len as i64 + 1))?;
-        }
-        Marker::Ext32 => {
-            let len = cur.read_u32::<BigEndian>()?;
-            cur.seek(SeekFrom::Current(len as i64 + 1))?;
-        }
-        Marker::Reserved => {
-            return Err(rmp::decode::ValueReadError::TypeMismatch(Marker::Reserved).into())
-        }
-    }
-    Ok(())
+    /// Server supports interactive transactions and streams (`IPROTO_STREAM_ID`)
+    pub streams: bool,
+
+    /// Server supports `IPROTO_BEGIN`, `IPROTO_COMMIT` and `IPROTO_ROLLBACK` requests
+    pub transactions: bool,
+
+    /// Server supports `MP_ERROR` MsgPack extension
+    pub error_extension: bool,
+
+    /// Server supports remote watchers (`IPROTO_WATCH`, `IPROTO_UNWATCH` requests)
+    pub watchers: bool,
+
+    /// Server supports pagination (`IPROTO_AFTER_POSITION` in select requests)
+    pub pagination: bool,
 }
 
+/// Error returned by the server in response to a request.
 #[derive(Debug)]
 pub struct ResponseError {
+    code: u32,
     message: String,
+    error: Option<RemoteError>,
+}
+
+impl ResponseError {
+    /// Error code (see [TarantoolErrorCode](../error/enum.TarantoolErrorCode.html)).
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+
+    /// Error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Structured error including the chain of causes.
+    ///
+    /// Available only if the server sends errors as `MP_ERROR` extension (Tarantool 2.4.1 and newer).
+    pub fn error(&self) -> Option<&RemoteError> {
+        self.error.as_ref()
+    }
+}
+
+/// Structured error decoded from `MP_ERROR` MsgPack extension.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RemoteError {
+    /// Error code
+    pub code: u32,
+
+    /// Error message
+    pub message: String,
+
+    /// Error type, e.g. `ClientError` or `CustomError`
+    pub error_type: String,
+
+    /// Additional fields, which depend on the error type (e.g. `custom_type` of `CustomError`)
+    pub fields: HashMap<String, serde_json::Value>,
+
+    /// The error which caused this one (see `box.error.prev` in Lua API)
+    pub prev: Option<Box<RemoteError>>,
 }
 
 impl Display for ResponseError {
@@ -71,7 +71,9 @@ impl RecvQueue {
                 match header {
                     Ok(header) => {
                         if header.status_code != 0 {
-                            return Err(decode_error(self.buffer.borrow_mut().by_ref())?.into());
+                            return Err(
+                                decode_error(self.buffer.borrow_mut().by_ref(), &header)?.into()
+                            );
                         }
 
                         payload_consumer(self.buffer.borrow_mut().by_ref(), &header)
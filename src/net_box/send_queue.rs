@@ -1,5 +1,6 @@
 use std::cell::{Cell, RefCell};
 use std::io::{self, Cursor, Write};
+use std::panic::{self, AssertUnwindSafe};
 use std::time::{Duration, SystemTime};
 
 use crate::error::Error;
@@ -42,13 +43,21 @@ impl SendQueue {
             let buffer = &mut *self.back_buffer.borrow_mut();
 
             let offset = buffer.position();
-            match write_to_buffer(buffer, sync, payload_producer) {
-                Err(err) => {
-                    // rollback buffer position on error
-                    buffer.set_position(offset);
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                write_to_buffer(buffer, sync, payload_producer)
+            }));
+            match result {
+                Ok(Ok(_)) => offset,
+                Ok(Err(err)) => {
+                    // rollback buffer on error
+                    rollback(buffer, offset);
                     return Err(err);
                 }
-                Ok(_) => offset,
+                Err(payload) => {
+                    // don't send a partially written request if the producer panics
+                    rollback(buffer, offset);
+                    panic::resume_unwind(payload);
+                }
             }
         };
 
@@ -108,6 +117,11 @@ impl SendQueue {
     }
 }
 
+fn rollback(buffer: &mut Cursor<Vec<u8>>, offset: u64) {
+    buffer.set_position(offset);
+    buffer.get_mut().truncate(offset as usize);
+}
+
 pub fn write_to_buffer<F>(
     buffer: &mut Cursor<Vec<u8>>,
     sync: u64,
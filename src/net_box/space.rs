@@ -1,5 +1,7 @@
 use std::rc::Rc;
 
+use serde::de::DeserializeOwned;
+
 use crate::error::Error;
 use crate::index::IteratorType;
 use crate::tuple::{AsTuple, Tuple};
@@ -124,4 +126,76 @@ impl RemoteSpace {
     {
         self.primary_key().delete(key, options)
     }
+
+    /// Same as [get](#method.get), but decodes the result into `T`.
+    pub fn get_typed<K, T>(&self, key: &K, options: &Options) -> Result<Option<T>, Error>
+    where
+        K: AsTuple,
+        T: DeserializeOwned,
+    {
+        decode_typed(self.get(key, options)?)
+    }
+
+    /// Same as [select](#method.select), but decodes each selected tuple into `T`.
+    pub fn select_typed<K, T>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+        options: &Options,
+    ) -> Result<Vec<T>, Error>
+    where
+        K: AsTuple,
+        T: DeserializeOwned,
+    {
+        self.select(iterator_type, key, options)?
+            .map(|tuple| tuple.into_struct())
+            .collect()
+    }
+
+    /// Same as [insert](#method.insert), but decodes the inserted tuple into `T`.
+    pub fn insert_typed<T>(&mut self, value: &T, options: &Options) -> Result<Option<T>, Error>
+    where
+        T: AsTuple + DeserializeOwned,
+    {
+        decode_typed(self.insert(value, options)?)
+    }
+
+    /// Same as [replace](#method.replace), but decodes the inserted tuple into `T`.
+    pub fn replace_typed<T>(&mut self, value: &T, options: &Options) -> Result<Option<T>, Error>
+    where
+        T: AsTuple + DeserializeOwned,
+    {
+        decode_typed(self.replace(value, options)?)
+    }
+
+    /// Same as [update](#method.update), but decodes the updated tuple into `T`.
+    pub fn update_typed<K, Op, T>(
+        &mut self,
+        key: &K,
+        ops: &Vec<Op>,
+        options: &Options,
+    ) -> Result<Option<T>, Error>
+    where
+        K: AsTuple,
+        Op: AsTuple,
+        T: DeserializeOwned,
+    {
+        decode_typed(self.update(key, ops, options)?)
+    }
+
+    /// Same as [delete](#method.delete), but decodes the deleted tuple into `T`.
+    pub fn delete_typed<K, T>(&mut self, key: &K, options: &Options) -> Result<Option<T>, Error>
+    where
+        K: AsTuple,
+        T: DeserializeOwned,
+    {
+        decode_typed(self.delete(key, options)?)
+    }
+}
+
+fn decode_typed<T>(tuple: Option<Tuple>) -> Result<Option<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    tuple.map(|tuple| tuple.into_struct()).transpose()
 }
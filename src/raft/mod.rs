@@ -196,7 +196,7 @@ impl Node {
         );
 
         match result {
-            Err(Error::IO(_)) => Ok(None),
+            Err(Error::IO(_)) | Err(Error::Io { .. }) => Ok(None),
             Err(e) => Err(e),
             Ok(response) => match response {
                 None => Ok(None),
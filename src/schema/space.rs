@@ -32,7 +32,9 @@ impl AsTuple for SpaceMetadata {}
 /// - `name` -  name of space, which should conform to the rules for object names.
 /// - `opts` - see SpaceCreateOptions struct.
 ///
-/// Returns a new space.
+/// Returns a new space. If a space with the same name or ID exists (and `if_not_exists` is not set), fails with
+/// [TarantoolErrorCode::SpaceExists](../../error/enum.TarantoolErrorCode.html#variant.SpaceExists), so this case can
+/// be told apart from other failures (see [Error::tarantool_code()](../../error/enum.Error.html#method.tarantool_code)).
 pub fn create_space(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Error> {
     // Check if space already exists.
     let space = Space::find(name);
@@ -60,15 +62,102 @@ pub fn create_space(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Erro
         }
     };
 
-    // Resolve ID of new space or use ID, specified in options.
+    // Resolve ID of new space or use ID, specified in options (it must not be taken).
     let space_id = match opts.id {
         None => resolve_new_space_id()?,
-        Some(id) => id,
+        Some(id) => {
+            let sys_space: Space = SystemSpace::Space.into();
+            if let Some(space) = sys_space.get(&(id,))? {
+                set_error!(
+                    TarantoolErrorCode::SpaceExists,
+                    "{} (id {} is taken by space '{}')",
+                    name,
+                    id,
+                    space.field::<String>(2)?.unwrap_or_default()
+                );
+                return Err(TarantoolError::last().into());
+            }
+            id
+        }
     };
 
     insert_new_space(space_id, user_id, name, opts)
 }
 
+/// Find a space or create it if it doesn't exist.
+///
+/// If the space exists and `opts.format` is set, the format of the existing space must have the same field names,
+/// types and nullability, otherwise fails with
+/// [TarantoolErrorCode::WrongSpaceFormat](../../error/enum.TarantoolErrorCode.html#variant.WrongSpaceFormat).
+/// Other options are not compared.
+pub fn ensure_space(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Error> {
+    let space = match Space::find(name) {
+        None => return create_space(name, opts),
+        Some(space) => space,
+    };
+
+    if let Some(format) = &opts.format {
+        let sys_space: Space = SystemSpace::Space.into();
+        let existing = match sys_space.get(&(space.id(),))? {
+            Some(metadata) => metadata
+                .field::<Vec<Map<String, Value>>>(6)?
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        let mismatch = if existing.len() != format.len() {
+            Some(format!(
+                "expected {} fields, got {}",
+                format.len(),
+                existing.len()
+            ))
+        } else {
+            format
+                .iter()
+                .zip(existing.iter())
+                .find_map(|(expected, actual)| {
+                    let name = actual.get("name").and_then(Value::as_str).unwrap_or("");
+                    let field_type = actual.get("type").and_then(Value::as_str).unwrap_or("");
+                    let is_nullable = actual
+                        .get("is_nullable")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false);
+                    if name != expected.name
+                        || !field_type.eq_ignore_ascii_case(&expected.field_type.to_string())
+                        || is_nullable != expected.is_nullable
+                    {
+                        Some(format!(
+                            "field '{}' is {}{}, expected '{}' of type {}{}",
+                            name,
+                            field_type,
+                            if is_nullable { " (nullable)" } else { "" },
+                            expected.name,
+                            expected.field_type.to_string().to_lowercase(),
+                            if expected.is_nullable {
+                                " (nullable)"
+                            } else {
+                                ""
+                            },
+                        ))
+                    } else {
+                        None
+                    }
+                })
+        };
+
+        if let Some(mismatch) = mismatch {
+            set_error!(
+                TarantoolErrorCode::WrongSpaceFormat,
+                "space '{}' exists with a different format: {}",
+                name,
+                mismatch
+            );
+            return Err(TarantoolError::last().into());
+        }
+    }
+    Ok(space)
+}
+
 fn resolve_new_space_id() -> Result<u32, Error> {
     let sys_space: Space = SystemSpace::Space.into();
     let mut sys_schema: Space = SystemSpace::Schema.into();
@@ -130,6 +219,44 @@ fn insert_new_space(
             let mut field_format = Map::<String, Value>::new();
             field_format.insert("name".to_string(), Value::String(ft.name.clone()));
             field_format.insert("type".to_string(), Value::String(ft.field_type.to_string()));
+            if ft.is_nullable {
+                field_format.insert("is_nullable".to_string(), Value::Bool(true));
+            }
+            if let Some(func_name) = &ft.constraint {
+                let mut constraint = Map::<String, Value>::new();
+                constraint.insert(
+                    func_name.clone(),
+                    Value::Number(Number::from(resolve_func_id(func_name)?)),
+                );
+                field_format.insert("constraint".to_string(), Value::Object(constraint));
+            }
+            if let Some(fk) = &ft.foreign_key {
+                // the space may refer to itself
+                let fk_space_id = if fk.space == name {
+                    id
+                } else {
+                    match Space::find(&fk.space) {
+                        Some(space) => space.id(),
+                        None => {
+                            set_error!(
+                                TarantoolErrorCode::NoSuchSpace,
+                                "Space '{}' does not exist",
+                                fk.space
+                            );
+                            return Err(TarantoolError::last().into());
+                        }
+                    }
+                };
+                let mut fk_def = Map::<String, Value>::new();
+                fk_def.insert(
+                    "space".to_string(),
+                    Value::Number(Number::from(fk_space_id)),
+                );
+                fk_def.insert("field".to_string(), Value::String(fk.field.clone()));
+                let mut foreign_key = Map::<String, Value>::new();
+                foreign_key.insert(fk.space.clone(), Value::Object(fk_def));
+                field_format.insert("foreign_key".to_string(), Value::Object(foreign_key));
+            }
             space_format.push(Value::Object(field_format));
         }
     }
@@ -151,6 +278,22 @@ fn insert_new_space(
     }
 }
 
+fn resolve_func_id(name: &str) -> Result<u32, Error> {
+    let sys_vfunc: Space = SystemSpace::VFunc.into();
+    let index = sys_vfunc.index("name").unwrap();
+    match index.get(&(name,))? {
+        Some(func) => Ok(func.field::<u32>(0)?.unwrap()),
+        None => {
+            set_error!(
+                TarantoolErrorCode::NoSuchFunction,
+                "Function '{}' does not exist",
+                name
+            );
+            Err(TarantoolError::last().into())
+        }
+    }
+}
+
 /// Drop a space.
 pub fn drop_space(space_id: u32) -> Result<(), Error> {
     // Delete automatically generated sequence.
@@ -1,35 +1,121 @@
 //! Box: sequences
 use crate::error::{Error, TarantoolError};
 use crate::ffi::tarantool as ffi;
+use crate::index::IteratorType;
 use crate::space::{Space, SystemSpace};
 use crate::tuple::AsTuple;
 
 /// A sequence is a generator of ordered integer values.
+///
+/// Sequence attributes (`name`, `step`, `min`, ...) are read from the `_sequence` system space when the sequence is
+/// looked up and are not refreshed afterwards.
 pub struct Sequence {
     seq_id: u32,
+    name: String,
+    step: i64,
+    min: i64,
+    max: i64,
+    start: i64,
+    cycle: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SequenceRow {
+    id: u32,
+    owner: u32,
+    name: String,
+    step: i64,
+    min: i64,
+    max: i64,
+    start: i64,
+    cache: i64,
+    cycle: bool,
+}
+
+impl AsTuple for SequenceRow {}
+
+impl From<SequenceRow> for Sequence {
+    fn from(row: SequenceRow) -> Self {
+        Sequence {
+            seq_id: row.id,
+            name: row.name,
+            step: row.step,
+            min: row.min,
+            max: row.max,
+            start: row.start,
+            cycle: row.cycle,
+        }
+    }
 }
 
 impl Sequence {
     /// Find sequence by name.
     pub fn find(name: &str) -> Result<Option<Self>, Error> {
-        #[derive(Serialize, Deserialize)]
-        struct Row {
-            seq_id: u32,
-        }
-
-        impl AsTuple for Row {}
-
         let space: Space = SystemSpace::Sequence.into();
         let name_idx = space.index("name").unwrap();
 
         Ok(match name_idx.get(&(name,))? {
             None => None,
-            Some(row_tuple) => Some(Sequence {
-                seq_id: row_tuple.into_struct::<Row>()?.seq_id,
-            }),
+            Some(row_tuple) => Some(row_tuple.into_struct::<SequenceRow>()?.into()),
         })
     }
 
+    /// Find sequence by id.
+    pub fn find_by_id(id: u32) -> Result<Option<Self>, Error> {
+        let space: Space = SystemSpace::Sequence.into();
+
+        Ok(match space.get(&(id,))? {
+            None => None,
+            Some(row_tuple) => Some(row_tuple.into_struct::<SequenceRow>()?.into()),
+        })
+    }
+
+    /// Returns all sequences registered in the `_sequence` system space, ordered by id.
+    pub fn all() -> Result<Vec<Self>, Error> {
+        let space: Space = SystemSpace::Sequence.into();
+
+        let mut result = Vec::new();
+        for row_tuple in space.select(IteratorType::All, &())? {
+            result.push(row_tuple.into_struct::<SequenceRow>()?.into());
+        }
+        Ok(result)
+    }
+
+    /// Sequence id.
+    pub fn id(&self) -> u32 {
+        self.seq_id
+    }
+
+    /// Sequence name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Value added to the previous value on each [next](#method.next) call.
+    pub fn step(&self) -> i64 {
+        self.step
+    }
+
+    /// Minimum value of the sequence.
+    pub fn min(&self) -> i64 {
+        self.min
+    }
+
+    /// Maximum value of the sequence.
+    pub fn max(&self) -> i64 {
+        self.max
+    }
+
+    /// First value returned by the sequence.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Whether the sequence wraps around on overflow.
+    pub fn cycle(&self) -> bool {
+        self.cycle
+    }
+
     /// Generate the next value and return it.
     ///
     /// The generation algorithm is simple:
@@ -6,18 +6,41 @@
 //! See also:
 //! - [Lua reference: Submodule box.space](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_space/)
 //! - [C API reference: Module box](https://www.tarantool.io/en/doc/latest/dev_guide/reference_capi/box/)
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::Hash;
+use std::ops::Bound;
 use std::os::raw::c_char;
 use std::ptr::null_mut;
 
 use num_traits::ToPrimitive;
+use serde::de::DeserializeOwned;
 use serde::{Serialize, Serializer};
 use serde_json::{Map, Value};
 
-use crate::error::{Error, TarantoolError};
+use crate::error::{Error, TarantoolError, TarantoolErrorCode};
+use crate::ffi::helper::new_c_str;
+use crate::ffi::lua;
 use crate::ffi::tarantool as ffi;
-use crate::index::{Index, IndexIterator, IteratorType};
-use crate::tuple::{AsTuple, Tuple};
+use crate::index::{
+    BiCursor, CountedIndexIterator, Index, IndexBase, IndexIterator, IndexRange, IndexStream,
+    IteratorType,
+};
+use crate::sequence::Sequence;
+use crate::tuple::{AsTuple, ToKey, Tuple};
+
+pub mod cdc;
+mod trigger;
+
+use cdc::{Backpressure, ChangeStream};
+
+/// A tuple of the primary index which is not found in a secondary index, see
+/// [Space::verify_indexes()](struct.Space.html#method.verify_indexes).
+pub struct IndexMismatch {
+    pub index_id: u32,
+    pub index_name: String,
+    pub tuple: Tuple,
+}
 
 /// End of the reserved range of system spaces.
 pub const SYSTEM_ID_MAX: u32 = 511;
@@ -115,7 +138,7 @@ impl Serialize for SpaceEngineType {
 /// (for details see [Options for box.schema.space.create](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_schema/space_create/)).
 ///
 /// `format` option is not supported at this moment.
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct SpaceCreateOptions {
     pub if_not_exists: bool,
     pub engine: Option<SpaceEngineType>,
@@ -149,6 +172,16 @@ pub struct SpaceFieldFormat {
     pub name: String,
     #[serde(alias = "type")]
     pub field_type: SpaceFieldType,
+    pub is_nullable: bool,
+    /// Name of a persistent function checking the field value (the constraint is named after the function).
+    /// The function must be created with `is_deterministic = true` before the space.
+    ///
+    /// Only for Tarantool version >= 2.10
+    pub constraint: Option<String>,
+    /// The field refers to a field of another space (or of the same space).
+    ///
+    /// Only for Tarantool version >= 2.10
+    pub foreign_key: Option<SpaceFieldForeignKey>,
 }
 
 impl SpaceFieldFormat {
@@ -156,10 +189,116 @@ impl SpaceFieldFormat {
         return SpaceFieldFormat {
             name: name.to_string(),
             field_type: ft,
+            is_nullable: false,
+            constraint: None,
+            foreign_key: None,
         };
     }
 }
 
+/// Foreign key of a space field: a value inserted into the field must exist in `field` of the space `space`
+/// (the foreign field must be indexed). The foreign key is named after the foreign space.
+///
+/// Violations fail with `TarantoolErrorCode::FieldForeignKeyFailed`.
+#[derive(Clone, Debug, Serialize)]
+pub struct SpaceFieldForeignKey {
+    /// Name of the foreign space.
+    pub space: String,
+    /// Name of the field in the foreign space.
+    pub field: String,
+}
+
+/// Must be implemented for types which describe tuples of a space, used by
+/// [Space::create_for](struct.Space.html#method.create_for).
+///
+/// Can be implemented with the [space_format!](../macro.space_format.html) macro.
+pub trait SpaceFormat {
+    /// Returns the space format: one entry for each tuple field.
+    fn format() -> Vec<SpaceFieldFormat>;
+}
+
+/// Must be implemented for types which can be used as fields of [SpaceFormat](trait.SpaceFormat.html) structs.
+pub trait SpaceField {
+    /// Type of the field in space format.
+    const FIELD_TYPE: SpaceFieldType;
+    /// Whether the field may contain `nil`.
+    const IS_NULLABLE: bool = false;
+}
+
+macro_rules! impl_space_field {
+    ($field_type:ident: $($t:ty),+) => {
+        $(impl SpaceField for $t {
+            const FIELD_TYPE: SpaceFieldType = SpaceFieldType::$field_type;
+        })+
+    };
+}
+
+impl_space_field!(Unsigned: u8, u16, u32, u64, usize);
+impl_space_field!(Integer: i8, i16, i32, i64, isize);
+impl_space_field!(Double: f32, f64);
+impl_space_field!(Boolean: bool);
+impl_space_field!(String: String, &str);
+
+impl<T> SpaceField for Vec<T> {
+    const FIELD_TYPE: SpaceFieldType = SpaceFieldType::Array;
+}
+
+impl<T> SpaceField for Option<T>
+where
+    T: SpaceField,
+{
+    const FIELD_TYPE: SpaceFieldType = T::FIELD_TYPE;
+    const IS_NULLABLE: bool = true;
+}
+
+/// Defines a struct and implements [SpaceFormat](space/trait.SpaceFormat.html) for it: each struct field becomes
+/// a field of the space format with the same name.
+///
+/// Field types are mapped with [SpaceField](space/trait.SpaceField.html) (`Option<T>` fields are nullable),
+/// a field of a type which doesn't implement it is a compile error.
+///
+/// Example:
+/// ```rust
+/// use tarantool::space_format;
+///
+/// space_format! {
+///     #[derive(Serialize, Deserialize)]
+///     pub struct User {
+///         pub id: u32,
+///         pub name: String,
+///         pub email: Option<String>,
+///     }
+/// }
+///
+/// let space = Space::create_for::<User>("users", &SpaceCreateOptions::default()).unwrap();
+/// ```
+#[macro_export]
+macro_rules! space_format {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl $crate::space::SpaceFormat for $name {
+            fn format() -> Vec<$crate::space::SpaceFieldFormat> {
+                vec![$($crate::space::SpaceFieldFormat {
+                    name: stringify!($field).to_string(),
+                    field_type: <$ty as $crate::space::SpaceField>::FIELD_TYPE,
+                    is_nullable: <$ty as $crate::space::SpaceField>::IS_NULLABLE,
+                    constraint: None,
+                    foreign_key: None,
+                }),*]
+            }
+        }
+    };
+}
+
 #[derive(Copy, Clone, Debug, Serialize)]
 pub enum SpaceFieldType {
     Any,
@@ -221,19 +360,59 @@ pub struct Space {
     id: u32,
 }
 
+impl fmt::Debug for Space {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("Space");
+        debug.field("id", &self.id);
+        if let Some(name) = self.meta_name() {
+            debug.field("name", &name);
+        }
+        debug.finish()
+    }
+}
+
 impl Space {
+    pub(crate) fn new(id: u32) -> Self {
+        Space { id }
+    }
+
     /// Create a space.
     /// (for details see [box.schema.space.create()](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_schema/space_create/)).
     ///
     /// - `name` -  name of space, which should conform to the rules for object names.
     /// - `opts` - see SpaceCreateOptions struct.
     ///
-    /// Returns a new space.
+    /// Returns a new space. Fails with [TarantoolErrorCode::SpaceExists](../error/enum.TarantoolErrorCode.html#variant.SpaceExists)
+    /// if the space already exists and `opts.if_not_exists` is not set.
     #[cfg(feature = "schema")]
     pub fn create(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Error> {
         return crate::schema::space::create_space(name, opts);
     }
 
+    /// Find a space by name or create it if it doesn't exist.
+    ///
+    /// If the space exists and `opts.format` is set, its format must match, otherwise fails with
+    /// [TarantoolErrorCode::WrongSpaceFormat](../error/enum.TarantoolErrorCode.html#variant.WrongSpaceFormat).
+    /// Other options of an existing space are not checked.
+    #[cfg(feature = "schema")]
+    pub fn ensure(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Error> {
+        crate::schema::space::ensure_space(name, opts)
+    }
+
+    /// Create a space with the format described by `T`
+    /// (see [SpaceFormat](trait.SpaceFormat.html)). The `format` field of `opts` is ignored.
+    #[cfg(feature = "schema")]
+    pub fn create_for<T>(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Error>
+    where
+        T: SpaceFormat,
+    {
+        let opts = SpaceCreateOptions {
+            format: Some(T::format()),
+            ..opts.clone()
+        };
+        Self::create(name, &opts)
+    }
+
     /// Drop a space.
     #[cfg(feature = "schema")]
     pub fn drop(&self) -> Result<(), Error> {
@@ -298,6 +477,38 @@ impl Space {
         }
     }
 
+    /// Find index by name, failing with an error which lists the indexes of the space if it is not found.
+    ///
+    /// Same as [index()](#method.index), but more helpful for diagnosing typos in index names.
+    pub fn try_index(&self, name: &str) -> Result<Index, Error> {
+        if let Some(index) = self.index(name) {
+            return Ok(index);
+        }
+
+        let vindex: Space = SystemSpace::VIndex.into();
+        let mut index_names = vec![];
+        for tuple in vindex.select(IteratorType::Eq, &self.id)? {
+            if let Some(index_name) = tuple.field::<String>(2)? {
+                index_names.push(index_name);
+            }
+        }
+
+        set_error!(
+            TarantoolErrorCode::NoSuchIndexName,
+            "No index '{}' in space '{}' (available: {})",
+            name,
+            self.meta_name().unwrap_or_else(|| self.id.to_string()),
+            index_names.join(", ")
+        );
+        Err(TarantoolError::last().into())
+    }
+
+    /// Read space name from `_vspace` system space.
+    fn meta_name(&self) -> Option<String> {
+        let vspace: Space = SystemSpace::VSpace.into();
+        vspace.get(&(self.id,)).ok()??.field::<String>(2).ok()?
+    }
+
     /// Returns index with id = 0
     #[inline(always)]
     pub fn primary_key(&self) -> Index {
@@ -392,6 +603,32 @@ impl Space {
         Ok(())
     }
 
+    /// Deletes all tuples and resets the sequence attached to the space (if any) to its start value.
+    ///
+    /// Handy to bring a space back to its initial state, e.g. between tests.
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.truncate()?;
+        if let Some(mut sequence) = self.sequence()? {
+            sequence.reset()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the sequence attached to the primary index of the space (see
+    /// [IndexOptions::sequence](../index/struct.IndexOptions.html#structfield.sequence)).
+    pub fn sequence(&self) -> Result<Option<Sequence>, Error> {
+        let space_sequence: Space = SystemSpace::SpaceSequence.into();
+        let seq_id = match space_sequence.get(&(self.id,))? {
+            None => return Ok(None),
+            Some(row_tuple) => row_tuple.field::<u32>(1)?,
+        };
+
+        match seq_id {
+            None => Ok(None),
+            Some(seq_id) => Sequence::find_by_id(seq_id),
+        }
+    }
+
     /// Return the number of tuples in the space.
     ///
     /// If compared with [space.count()](#method.count), this method works faster because [space.len()](#method.len)
@@ -401,20 +638,144 @@ impl Space {
         self.primary_key().len()
     }
 
-    /// Number of bytes in the space.
-    ///
-    /// This number, which is stored in Tarantool’s internal memory, represents the total number of bytes in all tuples,
-    /// not including index keys. For a measure of index size, see [index.bsize()](../index/struct.Index.html#method.bsize).
-    #[inline(always)]
+    /// Number of bytes used in memory by the space: tuple data ([data_bsize()](#method.data_bsize)) plus all
+    /// indexes ([index_bsize()](#method.index_bsize)).
     pub fn bsize(&self) -> Result<usize, Error> {
-        self.primary_key().bsize()
+        Ok(self.data_bsize()? + self.index_bsize()?)
+    }
+
+    /// Number of bytes in all tuples of the space, not including index keys.
+    ///
+    /// The C API has no counterpart of `space_object:bsize()`, so it is called via Lua. The value is maintained by
+    /// Tarantool, so it's cheap even on big spaces.
+    pub fn data_bsize(&self) -> Result<usize, Error> {
+        unsafe {
+            let state = lua::luaT_state();
+            let top = lua::lua_gettop(state);
+            let bsize_state = lua::lua_newthread(state);
+
+            // -- box.space[space_id]:bsize()
+            lua::lua_getglobal(bsize_state, new_c_str("box").as_ptr());
+            lua::lua_getfield(bsize_state, -1, new_c_str("space").as_ptr());
+            lua::lua_pushinteger(bsize_state, self.id as isize);
+            lua::lua_gettable(bsize_state, -2);
+            lua::lua_getfield(bsize_state, -1, new_c_str("bsize").as_ptr());
+            lua::lua_pushvalue(bsize_state, -2);
+            let result = if lua::luaT_call(bsize_state, 1, 1) != 0 {
+                Err(TarantoolError::last().into())
+            } else {
+                Ok(lua::lua_tointeger(bsize_state, -1) as usize)
+            };
+
+            lua::lua_settop(state, top);
+            result
+        }
+    }
+
+    /// Same as [data_bsize()](#method.data_bsize), but the sizes of the tuples are summed up by a full scan of the
+    /// primary index, so only the C API is used.
+    ///
+    /// It's slow on big spaces: use it only where Lua is not available.
+    pub fn scan_data_bsize(&self) -> Result<usize, Error> {
+        let mut result = 0;
+        let mut iterator = self.select(IteratorType::All, &())?;
+        while let Some(tuple) = iterator.try_next()? {
+            result += tuple.bsize();
+        }
+        Ok(result)
+    }
+
+    /// Subscribe to the changes of the space.
+    ///
+    /// Sets an `on_replace` trigger which collects every insert, update, delete etc. and sends them into a channel of
+    /// `capacity` events (must be greater than 0) once the transaction is committed. The changes of rolled back
+    /// transactions are discarded. `backpressure` defines what happens with a change when the channel is full. The
+    /// trigger is removed when the returned stream is dropped.
+    ///
+    /// The C API has no counterpart of `space_object:on_replace()` and `box.on_commit()`, so they are called via Lua.
+    ///
+    /// See also: [module cdc](cdc/index.html)
+    pub fn cdc(&self, capacity: usize, backpressure: Backpressure) -> Result<ChangeStream, Error> {
+        ChangeStream::new(self.id, capacity, backpressure)
+    }
+
+    /// Set an `on_replace` trigger which is called with the old and the new tuple of every change of the space made by
+    /// the current transaction.
+    ///
+    /// The trigger is removed when the transaction is committed or rolled back, so it never fires outside of it.
+    /// Returns `NoTransaction` error if there is no active transaction. Changes made by `f` itself don't call it again.
+    ///
+    /// The C API has no counterpart of `space_object:on_replace()` and `box.on_commit()`, so they are called via Lua.
+    ///
+    /// Example:
+    /// ```rust
+    /// start_transaction(|| -> Result<(), Error> {
+    ///     space.on_replace_txn(|_old, new| println!("inserted: {}", new.unwrap().to_json()))?;
+    ///     space.insert(&(1, "Alice"))?;
+    ///     Ok(())
+    /// })?;
+    /// ```
+    pub fn on_replace_txn<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnMut(Option<Tuple>, Option<Tuple>) + 'static,
+    {
+        trigger::set_on_replace_txn(self.id, f)
+    }
+
+    /// Check that every tuple of the primary index can be found by its key in each secondary index.
+    ///
+    /// This is a diagnostic tool for index corruption or bugs in `before_replace` triggers: it scans the whole space
+    /// and looks up every tuple in every secondary index, so it's slow on big spaces. Returns the tuples which are
+    /// missing in some index (or found there in a different version). `RTREE` indexes, functional indexes and indexes
+    /// over JSON paths (including multikey ones) are not checked.
+    pub fn verify_indexes(&self) -> Result<Vec<IndexMismatch>, Error> {
+        let vindex: Space = SystemSpace::VIndex.into();
+        let mut verifiers = vec![];
+        for tuple in vindex.select(IteratorType::Eq, &self.id)? {
+            match tuple.field::<u32>(1)? {
+                Some(index_id) if index_id != 0 => {
+                    if let Some(verifier) = Index::new(self.id, index_id).verifier()? {
+                        verifiers.push(verifier);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut mismatches = vec![];
+        let mut iterator = self.select(IteratorType::All, &())?;
+        while let Some(tuple) = iterator.try_next()? {
+            for verifier in &verifiers {
+                if !verifier.contains(&tuple)? {
+                    mismatches.push(IndexMismatch {
+                        index_id: verifier.index_id(),
+                        index_name: verifier.index_name().to_string(),
+                        tuple: tuple.clone(),
+                    });
+                }
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// Number of bytes used in memory by all indexes of the space (sum of
+    /// [index.bsize()](../index/struct.Index.html#method.bsize)).
+    pub fn index_bsize(&self) -> Result<usize, Error> {
+        let vindex: Space = SystemSpace::VIndex.into();
+        let mut result = 0;
+        for tuple in vindex.select(IteratorType::Eq, &self.id)? {
+            if let Some(index_id) = tuple.field::<u32>(1)? {
+                result += Index::new(self.id, index_id).bsize()?;
+            }
+        }
+        Ok(result)
     }
 
     /// Search for a tuple in the given space.
     #[inline(always)]
     pub fn get<K>(&self, key: &K) -> Result<Option<Tuple>, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
         self.primary_key().get(key)
     }
@@ -424,14 +785,139 @@ impl Space {
     ///
     /// - `type` - iterator type
     /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
+    ///
+    /// **Note:** an iterator opened inside a transaction must not be used after the transaction has ended, see
+    /// [index.select()](../index/struct.Index.html#method.select).
     #[inline(always)]
     pub fn select<K>(&self, iterator_type: IteratorType, key: &K) -> Result<IndexIterator, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
         self.primary_key().select(iterator_type, key)
     }
 
+    /// Same as [select()](#method.select), but the returned iterator knows the number of tuples.
+    ///
+    /// See also: [index.select_counted()](../index/struct.Index.html#method.select_counted)
+    #[inline(always)]
+    pub fn select_counted<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+    ) -> Result<CountedIndexIterator, Error>
+    where
+        K: ToKey,
+    {
+        self.primary_key().select_counted(iterator_type, key)
+    }
+
+    /// Same as [select()](#method.select), but collects the tuples into `buf`, which can be reused across calls.
+    ///
+    /// See also: [index.select_into()](../index/struct.Index.html#method.select_into)
+    #[inline(always)]
+    pub fn select_into<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+        buf: &mut Vec<Tuple>,
+    ) -> Result<usize, Error>
+    where
+        K: ToKey,
+    {
+        self.primary_key().select_into(iterator_type, key, buf)
+    }
+
+    /// Same as [select_into()](#method.select_into), but fails with `Error::TooManyRows` if more than `max_rows`
+    /// tuples are selected.
+    ///
+    /// See also: [index.select_into_capped()](../index/struct.Index.html#method.select_into_capped)
+    #[inline(always)]
+    pub fn select_into_capped<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+        max_rows: usize,
+        buf: &mut Vec<Tuple>,
+    ) -> Result<usize, Error>
+    where
+        K: ToKey,
+    {
+        self.primary_key()
+            .select_into_capped(iterator_type, key, max_rows, buf)
+    }
+
+    /// Select tuples with primary keys between `start` and `end`.
+    ///
+    /// See also: [index.range()](../index/struct.Index.html#method.range)
+    #[inline(always)]
+    pub fn range<K>(&self, start: Bound<&K>, end: Bound<&K>) -> Result<IndexRange, Error>
+    where
+        K: ToKey,
+    {
+        self.primary_key().range(start, end)
+    }
+
+    /// Create a bidirectional cursor over the primary index.
+    ///
+    /// See also: [index.cursor()](../index/struct.Index.html#method.cursor)
+    #[inline(always)]
+    pub fn cursor(&self) -> BiCursor {
+        self.primary_key().cursor()
+    }
+
+    /// Select tuples and decode each of them into `T` without creating intermediate tuples.
+    ///
+    /// See also: [index.select_stream()](../index/struct.Index.html#method.select_stream)
+    #[inline(always)]
+    pub fn select_stream<T, K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+    ) -> Result<IndexStream<T>, Error>
+    where
+        T: DeserializeOwned,
+        K: ToKey,
+    {
+        self.primary_key().select_stream(iterator_type, key)
+    }
+
+    /// Select tuples and collect them into a `HashMap` keyed by field `key_field` (zero-based).
+    ///
+    /// See also: [index.select_map()](../index/struct.Index.html#method.select_map)
+    #[inline(always)]
+    pub fn select_map<K, V, Q>(
+        &self,
+        iterator_type: IteratorType,
+        key: &Q,
+        key_field: u32,
+    ) -> Result<HashMap<K, V>, Error>
+    where
+        K: DeserializeOwned + Eq + Hash,
+        V: DeserializeOwned,
+        Q: ToKey,
+    {
+        self.primary_key().select_map(iterator_type, key, key_field)
+    }
+
+    /// Select tuples and group them by the value of field `group_field` (zero-based).
+    ///
+    /// See also: [index.select_grouped()](../index/struct.Index.html#method.select_grouped)
+    #[inline(always)]
+    pub fn select_grouped<K, V, Q>(
+        &self,
+        iterator_type: IteratorType,
+        key: &Q,
+        group_field: u32,
+    ) -> Result<HashMap<K, Vec<V>>, Error>
+    where
+        K: DeserializeOwned + Eq + Hash,
+        V: DeserializeOwned,
+        Q: ToKey,
+    {
+        self.primary_key()
+            .select_grouped(iterator_type, key, group_field)
+    }
+
     /// Return the number of tuples. If compared with [space.len()](#method.len), this method works slower because
     /// [space.count()](#method.count) scans the entire space to count the tuples.
     ///
@@ -439,7 +925,7 @@ impl Space {
     /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
     pub fn count<K>(&self, iterator_type: IteratorType, key: &K) -> Result<usize, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
         self.primary_key().count(iterator_type, key)
     }
@@ -452,7 +938,7 @@ impl Space {
     #[inline(always)]
     pub fn delete<K>(&mut self, key: &K) -> Result<Option<Tuple>, Error>
     where
-        K: AsTuple,
+        K: ToKey,
     {
         self.primary_key().delete(key)
     }
@@ -471,18 +957,39 @@ impl Space {
     /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
     /// - `ops` - encoded operations in MsgPack array format, e.g. `[['=', field_id, value], ['!', 2, 'xxx']]`
     ///
+    /// **Note:** field numbers in `ops` are **zero-based** here (unlike Lua API where they start from 1).
+    /// Use [space.update_with_base()](#method.update_with_base) to choose numbering explicitly.
+    ///
     /// Returns a new tuple.
     ///
     /// See also: [space.upsert()](#method.upsert)
     #[inline(always)]
     pub fn update<K, Op>(&mut self, key: &K, ops: &Vec<Op>) -> Result<Option<Tuple>, Error>
     where
-        K: AsTuple,
+        K: ToKey,
         Op: AsTuple,
     {
         self.primary_key().update(key, ops)
     }
 
+    /// Update a tuple using explicitly specified numbering of fields in `ops`.
+    ///
+    /// See also: [index.update_with_base()](../index/struct.Index.html#method.update_with_base),
+    /// [IndexBase](../index/enum.IndexBase.html)
+    #[inline(always)]
+    pub fn update_with_base<K, Op>(
+        &mut self,
+        key: &K,
+        ops: &Vec<Op>,
+        index_base: IndexBase,
+    ) -> Result<Option<Tuple>, Error>
+    where
+        K: ToKey,
+        Op: AsTuple,
+    {
+        self.primary_key().update_with_base(key, ops, index_base)
+    }
+
     /// Update or insert a tuple.
     ///
     /// If there is an existing tuple which matches the key fields of tuple, then the request has the same effect as
@@ -496,6 +1003,9 @@ impl Space {
     /// - `value` - encoded tuple in MsgPack Array format (`[field1, field2, ...]`)
     /// - `ops` - encoded operations in MsgPack array format, e.g. `[['=', field_id, value], ['!', 2, 'xxx']]`
     ///
+    /// **Note:** field numbers in `ops` are **zero-based** here (unlike Lua API where they start from 1).
+    /// Use [space.upsert_with_base()](#method.upsert_with_base) to choose numbering explicitly.
+    ///
     /// Returns a new tuple.
     ///
     /// See also: [space.update()](#method.update)
@@ -507,4 +1017,35 @@ impl Space {
     {
         self.primary_key().upsert(value, ops)
     }
+
+    /// Update or insert a tuple using explicitly specified numbering of fields in `ops`.
+    ///
+    /// See also: [index.upsert_with_base()](../index/struct.Index.html#method.upsert_with_base),
+    /// [IndexBase](../index/enum.IndexBase.html)
+    #[inline(always)]
+    pub fn upsert_with_base<T, Op>(
+        &mut self,
+        value: &T,
+        ops: &Vec<Op>,
+        index_base: IndexBase,
+    ) -> Result<Option<Tuple>, Error>
+    where
+        T: AsTuple,
+        Op: AsTuple,
+    {
+        self.primary_key().upsert_with_base(value, ops, index_base)
+    }
+
+    /// Read-modify-write a tuple atomically.
+    ///
+    /// See also: [index.modify()](../index/struct.Index.html#method.modify)
+    #[inline(always)]
+    pub fn modify<T, K, F>(&mut self, key: &K, f: F) -> Result<Option<T>, Error>
+    where
+        T: AsTuple + DeserializeOwned,
+        K: ToKey,
+        F: FnMut(&mut T),
+    {
+        self.primary_key().modify(key, f)
+    }
 }
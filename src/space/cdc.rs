@@ -0,0 +1,298 @@
+//! Change data capture
+//!
+//! [Space::cdc()](../struct.Space.html#method.cdc) sets an `on_replace` trigger on the space, which collects every
+//! change (insert, update, delete etc.) of a transaction as a [ChangeEvent](struct.ChangeEvent.html). The events are
+//! sent into a fiber channel when the transaction is committed and discarded when it is rolled back, so only the
+//! committed changes are published. The events are read from the returned [ChangeStream](struct.ChangeStream.html)
+//! in the order the transactions were committed.
+//!
+//! Example:
+//! ```rust
+//! use tarantool::space::Space;
+//! use tarantool::space::cdc::{Backpressure, ChangeOp};
+//!
+//! let space = Space::find("users").unwrap();
+//! let changes = space.cdc(100, Backpressure::Drop).unwrap();
+//! space.insert(&(1, "Alice")).unwrap();
+//!
+//! let event = changes.recv().unwrap();
+//! assert_eq!(event.op, ChangeOp::Insert);
+//! ```
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::ffi::CStr;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+use std::os::raw::{c_int, c_void};
+use std::rc::Rc;
+
+use crate::error::{Error, TarantoolErrorCode};
+use crate::ffi::lua;
+use crate::ffi::tarantool as ffi;
+use crate::fiber::channel::{Channel, Receiver, Sender};
+use crate::fiber::{Cond, Fiber};
+use crate::space::trigger;
+use crate::tuple::Tuple;
+
+/// What to do with a change when the channel of a [ChangeStream](struct.ChangeStream.html) is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Discard the event. The number of discarded events is returned by
+    /// [ChangeStream::dropped()](struct.ChangeStream.html#method.dropped).
+    Drop,
+
+    /// Keep the event in memory and send it as soon as the reader frees the room, so no event is lost.
+    ///
+    /// The writer itself is not blocked: the events are sent from the commit trigger, which must not yield.
+    Block,
+}
+
+/// Type of the request which changed the space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOp {
+    Insert,
+    Replace,
+    Update,
+    Upsert,
+    Delete,
+}
+
+/// A single change of the space.
+pub struct ChangeEvent {
+    pub op: ChangeOp,
+
+    /// The tuple before the change (`None` for an insert).
+    pub old: Option<Tuple>,
+
+    /// The tuple after the change (`None` for a delete).
+    pub new: Option<Tuple>,
+}
+
+/// Receiving end of the change events of a space, see [Space::cdc()](../struct.Space.html#method.cdc).
+///
+/// Dereferences to the channel [Receiver](../../fiber/channel/struct.Receiver.html). The trigger is removed from the
+/// space when the stream is dropped.
+pub struct ChangeStream {
+    receiver: Receiver<ChangeEvent>,
+    space_id: u32,
+    trigger_ref: c_int,
+    state: Rc<TriggerState>,
+    // the reference owned by the `on_replace` trigger
+    trigger_state: *const TriggerState,
+}
+
+/// Shared by the stream, its triggers and the fiber sending the backlog, so it is freed after all of them are gone.
+struct TriggerState {
+    sender: Sender<ChangeEvent>,
+    backpressure: Backpressure,
+    dropped: Cell<u64>,
+    is_closed: Cell<bool>,
+    // changes of the transactions in progress by the fiber running the transaction
+    pending: RefCell<HashMap<usize, Vec<ChangeEvent>>>,
+    // committed changes which didn't fit into the channel (`Backpressure::Block`)
+    backlog: RefCell<VecDeque<ChangeEvent>>,
+    backlog_cond: Cond,
+}
+
+impl TriggerState {
+    /// Sends the change of a committed transaction. Does not yield.
+    fn publish(&self, event: ChangeEvent) {
+        if self.is_closed.get() {
+            return;
+        }
+        let is_full = self.sender.len() >= self.sender.capacity();
+        match self.backpressure {
+            Backpressure::Drop if is_full => self.dropped.set(self.dropped.get() + 1),
+            Backpressure::Block if is_full || !self.backlog.borrow().is_empty() => {
+                self.backlog.borrow_mut().push_back(event);
+                self.backlog_cond.signal();
+            }
+            _ => {
+                let _ = self.sender.send(event);
+            }
+        }
+    }
+}
+
+/// Transaction which changed the space, released by its `on_commit` or `on_rollback` trigger.
+struct TxnHook {
+    state: Rc<TriggerState>,
+    fiber: usize,
+}
+
+impl ChangeStream {
+    pub(crate) fn new(
+        space_id: u32,
+        capacity: usize,
+        backpressure: Backpressure,
+    ) -> Result<Self, Error> {
+        ensure!(
+            capacity > 0,
+            TarantoolErrorCode::IllegalParams,
+            "Change stream capacity must be greater than 0"
+        );
+
+        let (sender, receiver) = Channel::new(capacity).split();
+        let state = Rc::new(TriggerState {
+            sender,
+            backpressure,
+            dropped: Cell::new(0),
+            is_closed: Cell::new(false),
+            pending: RefCell::new(HashMap::new()),
+            backlog: RefCell::new(VecDeque::new()),
+            backlog_cond: Cond::new(),
+        });
+
+        let trigger_state = Rc::into_raw(state.clone());
+        let trigger_ref = match unsafe {
+            trigger::set_on_replace(space_id, Some(on_replace), trigger_state as *mut c_void)
+        } {
+            Some(Ok(trigger_ref)) => trigger_ref,
+            Some(Err(error)) => {
+                drop(unsafe { Rc::from_raw(trigger_state) });
+                return Err(error);
+            }
+            None => {
+                drop(unsafe { Rc::from_raw(trigger_state) });
+                bail!(
+                    TarantoolErrorCode::NoSuchSpace,
+                    "Space '{}' does not exist",
+                    space_id
+                );
+            }
+        };
+
+        if backpressure == Backpressure::Block {
+            // the fiber keeps a pointer to the callback until it finishes; it's a zero-sized fn item, so leaking it
+            // doesn't allocate
+            let callback = Box::leak(Box::new(send_backlog));
+            let mut fiber: Fiber<Rc<TriggerState>> = Fiber::new("cdc_backlog", callback);
+            fiber.start(state.clone());
+        }
+
+        Ok(ChangeStream {
+            receiver,
+            space_id,
+            trigger_ref,
+            state,
+            trigger_state,
+        })
+    }
+
+    /// Number of events discarded because the channel was full (see [Backpressure::Drop](enum.Backpressure.html)).
+    pub fn dropped(&self) -> u64 {
+        self.state.dropped.get()
+    }
+}
+
+impl Deref for ChangeStream {
+    type Target = Receiver<ChangeEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl Drop for ChangeStream {
+    fn drop(&mut self) {
+        // the transactions in progress still end up in their commit triggers, which ignore the events now
+        self.state.is_closed.set(true);
+        self.state.backlog_cond.signal();
+
+        // if the trigger can't be removed, it may still be called, so its reference to the state is leaked
+        if unsafe { trigger::remove_on_replace(self.space_id, self.trigger_ref) } {
+            drop(unsafe { Rc::from_raw(self.trigger_state) });
+        }
+    }
+}
+
+/// Body of the fiber which moves the backlog into the channel as the reader frees the room.
+fn send_backlog(state: Box<Rc<TriggerState>>) -> i32 {
+    loop {
+        let event = state.backlog.borrow_mut().pop_front();
+        match event {
+            Some(event) => {
+                if state.sender.send(event).is_err() {
+                    break;
+                }
+            }
+            None if state.is_closed.get() => break,
+            None => {
+                state.backlog_cond.wait();
+            }
+        }
+    }
+    0
+}
+
+/// The trigger is called with `(old, new, space_name, request_type)` arguments.
+unsafe extern "C" fn on_replace(l: *mut lua::lua_State) -> c_int {
+    let state_ptr = lua::lua_touserdata(l, lua::lua_upvalueindex(1)) as *const TriggerState;
+    let state = &*state_ptr;
+    if state.is_closed.get() {
+        return 0;
+    }
+    let old = trigger::tuple_arg(l, 1);
+    let new = trigger::tuple_arg(l, 2);
+    let op = request_type(l, 4).unwrap_or(match (&old, &new) {
+        (None, _) => ChangeOp::Insert,
+        (_, None) => ChangeOp::Delete,
+        _ => ChangeOp::Replace,
+    });
+    let event = ChangeEvent { op, old, new };
+
+    let fiber = ffi::fiber_self() as usize;
+    if let Some(events) = state.pending.borrow_mut().get_mut(&fiber) {
+        events.push(event);
+        return 0;
+    }
+
+    // the first change of the transaction: collect the changes until it ends
+    let hook = Box::into_raw(Box::new(TxnHook {
+        state: Rc::clone(&ManuallyDrop::new(Rc::from_raw(state_ptr))),
+        fiber,
+    }));
+    match trigger::set_on_txn_end(Some(on_commit), Some(on_rollback), hook as *mut c_void) {
+        Ok(()) => {
+            state.pending.borrow_mut().insert(fiber, vec![event]);
+        }
+        Err(_) => {
+            drop(Box::from_raw(hook));
+            state.dropped.set(state.dropped.get() + 1);
+        }
+    }
+    0
+}
+
+unsafe extern "C" fn on_commit(l: *mut lua::lua_State) -> c_int {
+    let hook = Box::from_raw(lua::lua_touserdata(l, lua::lua_upvalueindex(1)) as *mut TxnHook);
+    let events = hook.state.pending.borrow_mut().remove(&hook.fiber);
+    for event in events.into_iter().flatten() {
+        hook.state.publish(event);
+    }
+    0
+}
+
+unsafe extern "C" fn on_rollback(l: *mut lua::lua_State) -> c_int {
+    let hook = Box::from_raw(lua::lua_touserdata(l, lua::lua_upvalueindex(1)) as *mut TxnHook);
+    hook.state.pending.borrow_mut().remove(&hook.fiber);
+    0
+}
+
+unsafe fn request_type(l: *mut lua::lua_State, idx: c_int) -> Option<ChangeOp> {
+    if lua::lua_type(l, idx) == lua::LUA_TNIL {
+        return None;
+    }
+    let name = lua::lua_tostring(l, idx);
+    if name.is_null() {
+        return None;
+    }
+    match CStr::from_ptr(name).to_bytes() {
+        b"INSERT" => Some(ChangeOp::Insert),
+        b"REPLACE" => Some(ChangeOp::Replace),
+        b"UPDATE" => Some(ChangeOp::Update),
+        b"UPSERT" => Some(ChangeOp::Upsert),
+        b"DELETE" => Some(ChangeOp::Delete),
+        _ => None,
+    }
+}
@@ -0,0 +1,221 @@
+//! Space and transaction triggers
+//!
+//! The C API has no counterpart of `space_object:on_replace()`, `box.on_commit()` and `box.on_rollback()`, so the
+//! triggers are set via Lua. A trigger is a C closure which gets a pointer to its data as the first upvalue.
+use std::cell::{Cell, RefCell};
+use std::os::raw::{c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::error::{Error, TarantoolError, TarantoolErrorCode};
+use crate::ffi::helper::new_c_str;
+use crate::ffi::lua;
+use crate::ffi::tarantool as ffi;
+use crate::tuple::Tuple;
+
+/// Sets `on_replace` trigger `f` on the space, returns the registry reference to the trigger function or `None` if
+/// there is no such space.
+pub(crate) unsafe fn set_on_replace(
+    space_id: u32,
+    f: lua::lua_CFunction,
+    data: *mut c_void,
+) -> Option<Result<c_int, Error>> {
+    let lua_state = lua::luaT_state();
+    let top = lua::lua_gettop(lua_state);
+    let l = lua::lua_newthread(lua_state);
+
+    // -- box.space[space_id]:on_replace(trigger)
+    let result = if push_space(l, space_id) {
+        lua::lua_getfield(l, -1, new_c_str("on_replace").as_ptr());
+        lua::lua_pushvalue(l, -2);
+        lua::lua_pushlightuserdata(l, data);
+        lua::lua_pushcclosure(l, f, 1);
+        lua::lua_pushvalue(l, -1);
+        let trigger_ref = lua::luaL_ref(l, lua::LUA_REGISTRYINDEX);
+        if lua::luaT_call(l, 2, 0) != 0 {
+            lua::luaL_unref(l, lua::LUA_REGISTRYINDEX, trigger_ref);
+            Some(Err(TarantoolError::last().into()))
+        } else {
+            Some(Ok(trigger_ref))
+        }
+    } else {
+        None
+    };
+
+    lua::lua_settop(lua_state, top);
+    result
+}
+
+/// Removes `on_replace` trigger set by [set_on_replace()](fn.set_on_replace.html).
+///
+/// Returns `false` if the trigger can't be removed: it may still be called then, so its data must stay alive.
+pub(crate) unsafe fn remove_on_replace(space_id: u32, trigger_ref: c_int) -> bool {
+    let lua_state = lua::luaT_state();
+    let top = lua::lua_gettop(lua_state);
+    let l = lua::lua_newthread(lua_state);
+
+    // -- box.space[space_id]:on_replace(nil, trigger)
+    // the trigger is already gone if the space was dropped
+    let is_removed = if push_space(l, space_id) {
+        lua::lua_getfield(l, -1, new_c_str("on_replace").as_ptr());
+        lua::lua_pushvalue(l, -2);
+        lua::lua_pushnil(l);
+        lua::lua_rawgeti(l, lua::LUA_REGISTRYINDEX, trigger_ref);
+        lua::luaT_call(l, 3, 0) == 0
+    } else {
+        true
+    };
+    if is_removed {
+        lua::luaL_unref(l, lua::LUA_REGISTRYINDEX, trigger_ref);
+    }
+
+    lua::lua_settop(lua_state, top);
+    is_removed
+}
+
+/// Sets `on_commit` and `on_rollback` triggers of the current transaction. Exactly one of them is called when the
+/// transaction ends, so `data` may be released by either of them. On error neither trigger is set.
+pub(crate) unsafe fn set_on_txn_end(
+    on_commit: lua::lua_CFunction,
+    on_rollback: lua::lua_CFunction,
+    data: *mut c_void,
+) -> Result<(), Error> {
+    let lua_state = lua::luaT_state();
+    let top = lua::lua_gettop(lua_state);
+    let l = lua::lua_newthread(lua_state);
+    lua::lua_getglobal(l, new_c_str("box").as_ptr());
+
+    // -- box.on_commit(on_commit)
+    lua::lua_pushlightuserdata(l, data);
+    lua::lua_pushcclosure(l, on_commit, 1);
+    lua::lua_getfield(l, -2, new_c_str("on_commit").as_ptr());
+    lua::lua_pushvalue(l, -2);
+    let mut result = if lua::luaT_call(l, 1, 0) != 0 {
+        Err(TarantoolError::last().into())
+    } else {
+        Ok(())
+    };
+
+    // -- box.on_rollback(on_rollback)
+    if result.is_ok() {
+        lua::lua_getfield(l, -2, new_c_str("on_rollback").as_ptr());
+        lua::lua_pushlightuserdata(l, data);
+        lua::lua_pushcclosure(l, on_rollback, 1);
+        if lua::luaT_call(l, 1, 0) != 0 {
+            result = Err(TarantoolError::last().into());
+
+            // -- box.on_commit(nil, on_commit)
+            lua::lua_getfield(l, -2, new_c_str("on_commit").as_ptr());
+            lua::lua_pushnil(l);
+            lua::lua_pushvalue(l, -3);
+            lua::luaT_call(l, 2, 0);
+        }
+    }
+
+    lua::lua_settop(lua_state, top);
+    result
+}
+
+/// `on_replace` trigger of a single transaction, see [Space::on_replace_txn()](../struct.Space.html#method.on_replace_txn).
+struct TxnTrigger {
+    callback: RefCell<Box<dyn FnMut(Option<Tuple>, Option<Tuple>)>>,
+    space_id: u32,
+    // the fiber running the transaction
+    fiber: usize,
+    trigger_ref: Cell<c_int>,
+}
+
+/// Sets `on_replace` trigger `f` on the space, which is called only for the changes of the current transaction and
+/// removed when the transaction ends.
+pub(crate) fn set_on_replace_txn<F>(space_id: u32, f: F) -> Result<(), Error>
+where
+    F: FnMut(Option<Tuple>, Option<Tuple>) + 'static,
+{
+    if !unsafe { ffi::box_txn() } {
+        bail!(
+            TarantoolErrorCode::NoTransaction,
+            "Transaction scoped trigger must be set inside a transaction"
+        );
+    }
+
+    // shared by the `on_replace` trigger and the transaction triggers, freed after `on_replace` trigger is removed
+    let data = Box::into_raw(Box::new(TxnTrigger {
+        callback: RefCell::new(Box::new(f)),
+        space_id,
+        fiber: unsafe { ffi::fiber_self() } as usize,
+        trigger_ref: Cell::new(0),
+    }));
+    let trigger_ref =
+        match unsafe { set_on_replace(space_id, Some(on_replace_txn), data as *mut c_void) } {
+            Some(Ok(trigger_ref)) => trigger_ref,
+            Some(Err(error)) => {
+                drop(unsafe { Box::from_raw(data) });
+                return Err(error);
+            }
+            None => {
+                drop(unsafe { Box::from_raw(data) });
+                bail!(
+                    TarantoolErrorCode::NoSuchSpace,
+                    "Space '{}' does not exist",
+                    space_id
+                );
+            }
+        };
+    unsafe { &*data }.trigger_ref.set(trigger_ref);
+
+    let result = unsafe { set_on_txn_end(Some(on_txn_end), Some(on_txn_end), data as *mut c_void) };
+    if result.is_err() {
+        unsafe { remove_txn_trigger(data) };
+    }
+    result
+}
+
+/// The trigger is called with `(old, new, space_name, request_type)` arguments.
+unsafe extern "C" fn on_replace_txn(l: *mut lua::lua_State) -> c_int {
+    let data = &*(lua::lua_touserdata(l, lua::lua_upvalueindex(1)) as *const TxnTrigger);
+    // other fibers may change the space while the transaction yields
+    if data.fiber != ffi::fiber_self() as usize {
+        return 0;
+    }
+    // the changes made by the callback itself are skipped
+    if let Ok(mut callback) = data.callback.try_borrow_mut() {
+        let old = tuple_arg(l, 1);
+        let new = tuple_arg(l, 2);
+        if panic::catch_unwind(AssertUnwindSafe(|| callback(old, new))).is_err() {
+            log::error!("on_replace trigger of space {} panicked", data.space_id);
+        }
+    }
+    0
+}
+
+/// Called by either `on_commit` or `on_rollback` trigger of the transaction.
+unsafe extern "C" fn on_txn_end(l: *mut lua::lua_State) -> c_int {
+    remove_txn_trigger(lua::lua_touserdata(l, lua::lua_upvalueindex(1)) as *mut TxnTrigger);
+    0
+}
+
+/// Removes `on_replace` trigger of the transaction and frees its data. If the trigger can't be removed, it may still
+/// be called, so the data is leaked.
+unsafe fn remove_txn_trigger(data: *mut TxnTrigger) {
+    if remove_on_replace((*data).space_id, (*data).trigger_ref.get()) {
+        drop(Box::from_raw(data));
+    }
+}
+
+/// Returns the tuple argument of a trigger or `None` if it's `nil`.
+pub(crate) unsafe fn tuple_arg(l: *mut lua::lua_State, idx: c_int) -> Option<Tuple> {
+    let ptr = lua::luaT_istuple(l, idx);
+    if ptr.is_null() {
+        None
+    } else {
+        Some(Tuple::from_ptr(ptr))
+    }
+}
+
+/// Pushes `box.space[space_id]` onto the stack, returns `false` if there is no such space.
+unsafe fn push_space(l: *mut lua::lua_State, space_id: u32) -> bool {
+    lua::lua_getglobal(l, new_c_str("box").as_ptr());
+    lua::lua_getfield(l, -1, new_c_str("space").as_ptr());
+    lua::lua_pushinteger(l, space_id as isize);
+    lua::lua_gettable(l, -2);
+    lua::lua_type(l, -1) != lua::LUA_TNIL
+}
@@ -8,17 +8,22 @@
 //! - [Tuples](https://www.tarantool.io/en/doc/2.2/book/box/data_model/#tuples)
 //! - [Lua reference: Submodule box.tuple](https://www.tarantool.io/en/doc/2.2/reference/reference_lua/box_tuple/)
 //! - [C API reference: Module tuple](https://www.tarantool.io/en/doc/2.2/dev_guide/reference_capi/tuple/)
+use std::cell::Cell;
 use std::cmp::Ordering;
-use std::io::Cursor;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
 use std::os::raw::{c_char, c_int};
 use std::ptr::copy_nonoverlapping;
 use std::slice::from_raw_parts;
 
+use byteorder::{BigEndian, ReadBytesExt};
 use num_traits::ToPrimitive;
 use rmp::Marker;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::de::{DeserializeOwned, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, SerializeMap, SerializeSeq};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{Map as JsonMap, Value as JsonValue};
 
-use crate::error::{Error, TarantoolError};
+use crate::error::{Error, FieldDecodeError, TarantoolError};
 use crate::ffi::tarantool as ffi;
 
 /// Tuple
@@ -36,12 +41,28 @@ impl Tuple {
     where
         T: AsTuple,
     {
-        let format = TupleFormat::default();
+        Self::new_with_format(value, &TupleFormat::default())
+    }
+
+    /// Creates new tuple from `value` with the given `format`.
+    ///
+    /// Fails if `value` doesn't match the format (e.g. a field has a wrong type). Use the format of a space tuple
+    /// (see [format()](#method.format)) to build tuples validated against the space format.
+    ///
+    /// Returns `Error::NotAnArray` if `value` is not serialized as a MsgPack array (e.g. it's a map or a scalar).
+    pub fn new_with_format<T>(value: &T, format: &TupleFormat) -> Result<Self, Error>
+    where
+        T: AsTuple,
+    {
         let buf = value.serialize_as_tuple()?;
+        ensure_array(unsafe { from_raw_parts(buf.as_ptr(), buf.len()) })?;
         let buf_ptr = buf.as_ptr() as *const c_char;
         let tuple_ptr = unsafe {
             ffi::box_tuple_new(format.inner, buf_ptr, buf_ptr.offset(buf.len() as isize))
         };
+        if tuple_ptr.is_null() {
+            return Err(TarantoolError::last().into());
+        }
 
         unsafe { ffi::box_tuple_ref(tuple_ptr) };
         Ok(Tuple { ptr: tuple_ptr })
@@ -61,6 +82,28 @@ impl Tuple {
         Tuple { ptr }
     }
 
+    /// Consumes the tuple, returning a raw pointer to the underlying `box_tuple_t`.
+    ///
+    /// The reference held by this `Tuple` is not released (`box_tuple_unref` is not called), so the tuple stays alive
+    /// until the pointer is passed back to [from_raw](#method.from_raw) or released manually with `box_tuple_unref`.
+    pub fn into_raw(self) -> *mut ffi::BoxTuple {
+        let ptr = self.ptr;
+        std::mem::forget(self);
+        ptr
+    }
+
+    /// Constructs a tuple from a raw pointer, taking ownership of one reference.
+    ///
+    /// The reference count is not incremented: the returned `Tuple` will call `box_tuple_unref` when dropped.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid tuple and the caller must own one reference to it, e.g. the pointer was returned
+    /// by [into_raw](#method.into_raw) or `box_tuple_ref` was called on it. The reference must not be released
+    /// anywhere else afterwards.
+    pub unsafe fn from_raw(ptr: *mut ffi::BoxTuple) -> Self {
+        Tuple { ptr }
+    }
+
     /// Return the number of fields in tuple (the size of MsgPack Array).
     pub fn len(&self) -> u32 {
         unsafe { ffi::box_tuple_field_count(self.ptr) }
@@ -79,11 +122,22 @@ impl Tuple {
         unsafe { ffi::box_tuple_bsize(self.ptr) }
     }
 
+    /// Same as [bsize()](#method.bsize): the number of bytes of the MsgPack encoded tuple.
+    #[inline(always)]
+    pub fn byte_size(&self) -> usize {
+        self.bsize()
+    }
+
+    /// Returns `true` if the tuple has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Return the associated format.
+    ///
+    /// The format is reference counted, so it stays alive while the returned handle exists.
     pub fn format(&self) -> TupleFormat {
-        TupleFormat {
-            inner: unsafe { ffi::box_tuple_format(self.ptr) },
-        }
+        TupleFormat::from_ptr(unsafe { ffi::box_tuple_format(self.ptr) })
     }
 
     /// Allocate and initialize a new `Tuple` iterator. The `Tuple` iterator
@@ -131,13 +185,29 @@ impl Tuple {
         field_value_from_ptr(result_ptr as *mut u8)
     }
 
+    /// Returns tuple contents (MsgPack array) without copying.
+    ///
+    /// The returned slice borrows the tuple memory, so it is valid as long as the tuple is alive.
+    pub fn as_raw_bytes(&self) -> &[u8] {
+        unsafe { raw_bytes(self.ptr) }
+    }
+
+    /// Returns an iterator over the fields of the tuple as raw MsgPack values, without decoding them.
+    ///
+    /// Useful to forward or hash fields as is. Use `.enumerate()` to get field numbers as well.
+    pub fn raw_fields(&self) -> RawFields<'_> {
+        let mut cur = Cursor::new(self.as_raw_bytes());
+        let remaining = rmp::decode::read_array_len(&mut cur).unwrap_or(0);
+        RawFields { cur, remaining }
+    }
+
     /// Deserializes tuple contents into structure of type `T`
     pub fn as_struct<T>(&self) -> Result<T, Error>
     where
         T: DeserializeOwned,
     {
         let raw_data = self.as_buffer()?;
-        Ok(rmp_serde::from_read::<_, T>(Cursor::new(raw_data))?)
+        decode_fields(Cursor::new(raw_data))
     }
 
     #[inline]
@@ -163,11 +233,126 @@ impl Tuple {
         self.as_struct()
     }
 
+    /// Decodes only the fields listed in `fields` (zero-based, in the given order) into `T`.
+    ///
+    /// Other fields are skipped by their MessagePack lengths without being decoded, which is cheaper for wide tuples.
+    /// Fields missing in the tuple are decoded as `nil`.
+    pub fn project<T>(&self, fields: &[u32]) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        decode_fields(&project_fields(self.as_raw_bytes(), fields)?[..])
+    }
+
+    /// Renders tuple fields as a JSON array, e.g. for logging.
+    ///
+    /// This is best-effort: binary fields and MsgPack extensions which have no JSON counterpart are rendered as
+    /// strings. Decimals and UUIDs are written in their usual text form, binary data and other extensions are
+    /// base64-encoded.
+    pub fn to_json(&self) -> String {
+        let mut cur = Cursor::new(self.as_raw_bytes());
+        decode_json_value(&mut cur)
+            .unwrap_or(JsonValue::Null)
+            .to_string()
+    }
+
+    /// Applies update operations to a copy of the tuple, e.g. `[['=', 2, 'xxx'], ['!', 3, 'yyy']]`, and returns the
+    /// new tuple. The tuple itself is not changed.
+    ///
+    /// **Note:** field numbers in `ops` are **one-based** here (as in Lua API).
+    pub fn apply_ops<Op>(&self, ops: &[Op]) -> Result<Tuple, Error>
+    where
+        Op: AsTuple,
+    {
+        self.apply_ops_buf(&rmp_serde::to_vec(ops)?)
+    }
+
+    fn apply_ops_buf(&self, ops_buf: &[u8]) -> Result<Tuple, Error> {
+        let ops_buf_ptr = ops_buf.as_ptr() as *const c_char;
+        let result_ptr =
+            unsafe { ffi::box_tuple_update(self.ptr, ops_buf_ptr, ops_buf_ptr.add(ops_buf.len())) };
+        if result_ptr.is_null() {
+            return Err(TarantoolError::last().into());
+        }
+        Ok(Tuple::from_ptr(result_ptr))
+    }
+
+    /// Same as [apply_ops()](#method.apply_ops), but also returns the values of fields before they were changed
+    /// (e.g. to build an audit diff).
+    ///
+    /// There is an [OldValue](struct.OldValue.html) for each operation which targets an existing field by number.
+    /// Operations are applied one by one, so the old value is the one just before the operation. Fields created by
+    /// `!` (insert) or by assigning to the field next to the last one have no old value; neither do fields
+    /// specified by name.
+    pub fn apply_ops_returning<Op>(&self, ops: &[Op]) -> Result<(Tuple, Vec<OldValue>), Error>
+    where
+        Op: AsTuple,
+    {
+        let mut tuple = self.clone();
+        let mut old_values = Vec::new();
+        for (op_index, op) in ops.iter().enumerate() {
+            let op_buf = op.serialize_as_tuple()?;
+            let (op_name, field) =
+                decode_update_op(unsafe { from_raw_parts(op_buf.as_ptr(), op_buf.len()) })?;
+
+            let field_count = tuple.len() as i64;
+            let fieldno = match field {
+                Some(field) if field > 0 => Some(field - 1),
+                Some(field) if field < 0 => Some(field_count + field),
+                _ => None,
+            };
+            match fieldno {
+                Some(fieldno) if op_name != "!" && fieldno >= 0 && fieldno < field_count => {
+                    old_values.push(OldValue {
+                        op_index,
+                        fieldno: fieldno as u32,
+                        data: tuple.raw_fields().nth(fieldno as usize).unwrap().to_vec(),
+                    });
+                }
+                _ => {}
+            }
+
+            tuple = tuple.apply_ops_buf(&rmp_serde::to_vec(&[op])?)?;
+        }
+        Ok((tuple, old_values))
+    }
+
     pub(crate) fn into_ptr(self) -> *mut ffi::BoxTuple {
         self.ptr
     }
 }
 
+/// Value of a field before an update operation, see [Tuple::apply_ops_returning](struct.Tuple.html#method.apply_ops_returning).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OldValue {
+    /// Index of the operation in `ops`.
+    pub op_index: usize,
+    /// Zero-based number of the field (as in [Tuple::field](struct.Tuple.html#method.field)).
+    pub fieldno: u32,
+    /// The field value (MsgPack).
+    pub data: Vec<u8>,
+}
+
+impl OldValue {
+    /// Deserializes the field value.
+    pub fn decode<T>(&self) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(rmp_serde::from_read_ref(&self.data)?)
+    }
+}
+
+/// Returns the name of an update operation and its field number (`None` if the field is specified by name).
+fn decode_update_op(op: &[u8]) -> Result<(String, Option<i64>), Error> {
+    let mut cur = Cursor::new(op);
+    rmp::decode::read_array_len(&mut cur)?;
+    let mut name = vec![0; rmp::decode::read_str_len(&mut cur)? as usize];
+    cur.read_exact(&mut name)?;
+    let field = rmp::decode::read_int(&mut cur).ok();
+    Ok((String::from_utf8_lossy(&name).into_owned(), field))
+}
+
 impl Drop for Tuple {
     fn drop(&mut self) {
         unsafe { ffi::box_tuple_unref(self.ptr) };
@@ -191,6 +376,14 @@ pub trait AsTuple: Serialize {
     }
 }
 
+/// A tuple is inserted as is: its MsgPack data is copied without being decoded. For the same reason a tuple can be
+/// used as a key (e.g. the result of [Index::extract_key](../index/struct.Index.html#method.extract_key)).
+impl AsTuple for Tuple {
+    fn serialize_as_tuple(&self) -> Result<TupleBuffer, Error> {
+        Ok(TupleBuffer::Vector(self.as_raw_bytes().to_vec()))
+    }
+}
+
 impl AsTuple for () {
     fn serialize_as_tuple(&self) -> Result<TupleBuffer, Error> {
         Ok(rmp_serde::to_vec(&Vec::<()>::new())?.into())
@@ -224,6 +417,91 @@ where
 {
 }
 
+/// Must be implemented for types, which will be used as index keys.
+///
+/// A key is encoded as MsgPack array (`[part1, part2, ...]`). Implemented for all [AsTuple](trait.AsTuple.html)
+/// types (for composite keys like `(5, "abc")`) and for scalars, which are encoded as a single-part key, so
+/// `index.get(&5)` is the same as `index.get(&(5,))`.
+pub trait ToKey {
+    /// Encodes the key into MsgPack array.
+    fn serialize_as_key(&self) -> Result<TupleBuffer, Error>;
+}
+
+impl<T> ToKey for T
+where
+    T: AsTuple,
+{
+    #[inline(always)]
+    fn serialize_as_key(&self) -> Result<TupleBuffer, Error> {
+        self.serialize_as_tuple()
+    }
+}
+
+macro_rules! impl_scalar_key {
+    ($($t:ty),+) => {
+        $(impl ToKey for $t {
+            #[inline(always)]
+            fn serialize_as_key(&self) -> Result<TupleBuffer, Error> {
+                (self,).serialize_as_tuple()
+            }
+        })+
+    };
+}
+
+impl_scalar_key!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, bool, String, &str);
+
+/// Encodes `parts` (a tuple of key parts) as a key. Used by [tuple_key!](../macro.tuple_key.html).
+#[doc(hidden)]
+pub fn key_from_parts<T>(parts: &T) -> Result<TupleBuffer, Error>
+where
+    T: Serialize,
+{
+    Ok(rmp_serde::to_vec(parts)?.into())
+}
+
+/// Defines a struct and implements [ToKey](tuple/trait.ToKey.html) for it: the fields are encoded as key parts in
+/// the order of declaration, which is clearer than a positional tuple for multi-part keys.
+///
+/// Field types must implement `Serialize`, the struct itself doesn't have to.
+///
+/// Example:
+/// ```rust
+/// use tarantool::tuple_key;
+///
+/// tuple_key! {
+///     #[derive(Debug, Clone)]
+///     pub struct UserTsKey {
+///         pub user_id: u64,
+///         pub ts: i64,
+///     }
+/// }
+///
+/// // the same as `index.get(&(1, 1600000000))`
+/// let user = index.get(&UserTsKey { user_id: 1, ts: 1600000000 }).unwrap();
+/// ```
+#[macro_export]
+macro_rules! tuple_key {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl $crate::tuple::ToKey for $name {
+            fn serialize_as_key(
+                &self,
+            ) -> Result<$crate::tuple::TupleBuffer, $crate::error::Error> {
+                $crate::tuple::key_from_parts(&($(&self.$field,)*))
+            }
+        }
+    };
+}
+
 /// Buffer containing tuple contents (MsgPack array)
 ///
 /// If buffer is allocated within transaction: will be disposed after transaction ended (committed or dropped).
@@ -271,15 +549,66 @@ impl From<Vec<u8>> for TupleBuffer {
 ///
 /// Each Tuple has associated format (class). Default format is used to
 /// create tuples which are not attach to any particular space.
+///
+/// Cloning a format returns a new handle to the same format.
 pub struct TupleFormat {
     inner: *mut ffi::BoxTupleFormat,
 }
 
+impl TupleFormat {
+    fn from_ptr(inner: *mut ffi::BoxTupleFormat) -> Self {
+        unsafe { ffi::box_tuple_format_ref(inner) };
+        TupleFormat { inner }
+    }
+}
+
 impl Default for TupleFormat {
     fn default() -> Self {
-        TupleFormat {
-            inner: unsafe { ffi::box_tuple_format_default() },
+        TupleFormat::from_ptr(unsafe { ffi::box_tuple_format_default() })
+    }
+}
+
+impl Clone for TupleFormat {
+    fn clone(&self) -> Self {
+        TupleFormat::from_ptr(self.inner)
+    }
+}
+
+impl PartialEq for TupleFormat {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl Drop for TupleFormat {
+    fn drop(&mut self) {
+        unsafe { ffi::box_tuple_format_unref(self.inner) };
+    }
+}
+
+/// Iterator over raw MsgPack fields of a tuple, see [Tuple::raw_fields](struct.Tuple.html#method.raw_fields).
+pub struct RawFields<'a> {
+    cur: Cursor<&'a [u8]>,
+    remaining: u32,
+}
+
+impl<'a> Iterator for RawFields<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
         }
+        self.remaining -= 1;
+
+        let start = self.cur.position() as usize;
+        skip_msgpack(&mut self.cur).ok()?;
+        let data: &'a [u8] = self.cur.get_ref();
+        data.get(start..self.cur.position() as usize)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining as usize))
     }
 }
 
@@ -427,6 +756,12 @@ impl KeyDef {
         let key_buf_ptr = key_buf.as_ptr() as *const c_char;
         unsafe { ffi::box_tuple_compare_with_key(tuple.ptr, key_buf_ptr, self.inner) }.cmp(&0)
     }
+
+    /// Same as [compare_with_key()](#method.compare_with_key), but takes an already encoded key.
+    pub(crate) fn compare_with_key_buf(&self, tuple: &Tuple, key_buf: &TupleBuffer) -> Ordering {
+        let key_buf_ptr = key_buf.as_ptr() as *const c_char;
+        unsafe { ffi::box_tuple_compare_with_key(tuple.ptr, key_buf_ptr, self.inner) }.cmp(&0)
+    }
 }
 
 impl Drop for KeyDef {
@@ -481,6 +816,716 @@ where
     })
 }
 
+/// Returns the MsgPack data of the tuple pointed by `ptr`.
+///
+/// The C API only gives access to the fields (`box_tuple_field`), so the array header is found by looking at the
+/// bytes preceding the first field: depending on the field count it is encoded with 1, 3 or 5 bytes.
+///
+/// # Safety
+/// `ptr` must point to a valid tuple, which must stay alive during `'a`.
+pub(crate) unsafe fn raw_bytes<'a>(ptr: *const ffi::BoxTuple) -> &'a [u8] {
+    const EMPTY_ARRAY: &[u8] = &[0x90];
+
+    let field_count = ffi::box_tuple_field_count(ptr);
+    let first_field = ffi::box_tuple_field(ptr, 0) as *const u8;
+    if first_field.is_null() {
+        return EMPTY_ARRAY;
+    }
+
+    let header_len = if field_count < 16 && *first_field.sub(1) == 0x90 | field_count as u8 {
+        1
+    } else if field_count < 0x10000
+        && *first_field.sub(3) == 0xdc
+        && u16::from_be_bytes([*first_field.sub(2), *first_field.sub(1)]) as u32 == field_count
+    {
+        3
+    } else {
+        5
+    };
+
+    from_raw_parts(first_field.sub(header_len), ffi::box_tuple_bsize(ptr))
+}
+
+/// Checks that `data` starts with a MsgPack array (a tuple can't be built from a map or a scalar).
+pub(crate) fn ensure_array(data: &[u8]) -> Result<(), Error> {
+    match data.first().map(|byte| Marker::from_u8(*byte)) {
+        Some(Marker::FixArray(_)) | Some(Marker::Array16) | Some(Marker::Array32) => Ok(()),
+        _ => Err(Error::NotAnArray),
+    }
+}
+
+const MP_DECIMAL: i8 = 1;
+const MP_UUID: i8 = 2;
+
+/// Decodes one MsgPack value from `cur` as JSON (see [Tuple::to_json()](struct.Tuple.html#method.to_json)).
+fn decode_json_value(cur: &mut Cursor<&[u8]>) -> Result<JsonValue, Error> {
+    Ok(match rmp::decode::read_marker(cur)? {
+        Marker::Null => JsonValue::Null,
+        Marker::True => JsonValue::Bool(true),
+        Marker::False => JsonValue::Bool(false),
+        Marker::FixPos(v) => v.into(),
+        Marker::FixNeg(v) => v.into(),
+        Marker::U8 => cur.read_u8()?.into(),
+        Marker::U16 => cur.read_u16::<BigEndian>()?.into(),
+        Marker::U32 => cur.read_u32::<BigEndian>()?.into(),
+        Marker::U64 => cur.read_u64::<BigEndian>()?.into(),
+        Marker::I8 => cur.read_i8()?.into(),
+        Marker::I16 => cur.read_i16::<BigEndian>()?.into(),
+        Marker::I32 => cur.read_i32::<BigEndian>()?.into(),
+        Marker::I64 => cur.read_i64::<BigEndian>()?.into(),
+        Marker::F32 => (cur.read_f32::<BigEndian>()? as f64).into(),
+        Marker::F64 => cur.read_f64::<BigEndian>()?.into(),
+        Marker::FixStr(len) => read_json_str(cur, len as usize)?,
+        Marker::Str8 => {
+            let len = cur.read_u8()?;
+            read_json_str(cur, len as usize)?
+        }
+        Marker::Str16 => {
+            let len = cur.read_u16::<BigEndian>()?;
+            read_json_str(cur, len as usize)?
+        }
+        Marker::Str32 => {
+            let len = cur.read_u32::<BigEndian>()?;
+            read_json_str(cur, len as usize)?
+        }
+        Marker::Bin8 => {
+            let len = cur.read_u8()?;
+            base64::encode(read_bytes(cur, len as usize)?).into()
+        }
+        Marker::Bin16 => {
+            let len = cur.read_u16::<BigEndian>()?;
+            base64::encode(read_bytes(cur, len as usize)?).into()
+        }
+        Marker::Bin32 => {
+            let len = cur.read_u32::<BigEndian>()?;
+            base64::encode(read_bytes(cur, len as usize)?).into()
+        }
+        Marker::FixArray(len) => read_json_array(cur, len as usize)?,
+        Marker::Array16 => {
+            let len = cur.read_u16::<BigEndian>()?;
+            read_json_array(cur, len as usize)?
+        }
+        Marker::Array32 => {
+            let len = cur.read_u32::<BigEndian>()?;
+            read_json_array(cur, len as usize)?
+        }
+        Marker::FixMap(len) => read_json_map(cur, len as usize)?,
+        Marker::Map16 => {
+            let len = cur.read_u16::<BigEndian>()?;
+            read_json_map(cur, len as usize)?
+        }
+        Marker::Map32 => {
+            let len = cur.read_u32::<BigEndian>()?;
+            read_json_map(cur, len as usize)?
+        }
+        Marker::FixExt1 => read_json_ext(cur, 1)?,
+        Marker::FixExt2 => read_json_ext(cur, 2)?,
+        Marker::FixExt4 => read_json_ext(cur, 4)?,
+        Marker::FixExt8 => read_json_ext(cur, 8)?,
+        Marker::FixExt16 => read_json_ext(cur, 16)?,
+        Marker::Ext8 => {
+            let len = cur.read_u8()?;
+            read_json_ext(cur, len as usize)?
+        }
+        Marker::Ext16 => {
+            let len = cur.read_u16::<BigEndian>()?;
+            read_json_ext(cur, len as usize)?
+        }
+        Marker::Ext32 => {
+            let len = cur.read_u32::<BigEndian>()?;
+            read_json_ext(cur, len as usize)?
+        }
+        Marker::Reserved => return Err(io::Error::from(io::ErrorKind::InvalidData).into()),
+    })
+}
+
+fn read_bytes<'a>(cur: &mut Cursor<&'a [u8]>, len: usize) -> Result<&'a [u8], Error> {
+    let start = cur.position() as usize;
+    let data = cur
+        .get_ref()
+        .get(start..start + len)
+        .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+    cur.set_position((start + len) as u64);
+    Ok(data)
+}
+
+fn read_json_str(cur: &mut Cursor<&[u8]>, len: usize) -> Result<JsonValue, Error> {
+    Ok(String::from_utf8_lossy(read_bytes(cur, len)?).into())
+}
+
+fn read_json_array(cur: &mut Cursor<&[u8]>, len: usize) -> Result<JsonValue, Error> {
+    let mut items = Vec::with_capacity(len);
+    for _ in 0..len {
+        items.push(decode_json_value(cur)?);
+    }
+    Ok(JsonValue::Array(items))
+}
+
+fn read_json_map(cur: &mut Cursor<&[u8]>, len: usize) -> Result<JsonValue, Error> {
+    let mut map = JsonMap::new();
+    for _ in 0..len {
+        let key = match decode_json_value(cur)? {
+            JsonValue::String(key) => key,
+            key => key.to_string(),
+        };
+        map.insert(key, decode_json_value(cur)?);
+    }
+    Ok(JsonValue::Object(map))
+}
+
+fn read_json_ext(cur: &mut Cursor<&[u8]>, len: usize) -> Result<JsonValue, Error> {
+    let ext_type = cur.read_i8()?;
+    let data = read_bytes(cur, len)?;
+    Ok(match ext_type {
+        MP_DECIMAL => decode_decimal(data)?.into(),
+        MP_UUID if data.len() == 16 => {
+            let hex: String = data.iter().map(|b| format!("{:02x}", b)).collect();
+            format!(
+                "{}-{}-{}-{}-{}",
+                &hex[0..8],
+                &hex[8..12],
+                &hex[12..16],
+                &hex[16..20],
+                &hex[20..32]
+            )
+            .into()
+        }
+        _ => base64::encode(data).into(),
+    })
+}
+
+/// Decodes Tarantool decimal: MsgPack int scale followed by packed BCD digits with the sign in the last nibble.
+fn decode_decimal(data: &[u8]) -> Result<String, Error> {
+    let mut cur = Cursor::new(data);
+    let scale: i32 = rmp::decode::read_int(&mut cur)?;
+    let bcd = &data[cur.position() as usize..];
+
+    let mut digits = String::with_capacity(bcd.len() * 2);
+    let mut is_negative = false;
+    for (i, byte) in bcd.iter().enumerate() {
+        let (high, low) = (byte >> 4, byte & 0x0f);
+        digits.push((b'0' + high) as char);
+        if i + 1 == bcd.len() {
+            is_negative = low == 0x0b || low == 0x0d;
+        } else {
+            digits.push((b'0' + low) as char);
+        }
+    }
+
+    let mut digits = digits.trim_start_matches('0').to_string();
+    if scale < 0 {
+        digits.extend(std::iter::repeat('0').take(-scale as usize));
+    } else if scale > 0 {
+        let scale = scale as usize;
+        if digits.len() <= scale {
+            digits = format!("{}{}", "0".repeat(scale + 1 - digits.len()), digits);
+        }
+        digits.insert(digits.len() - scale, '.');
+    }
+    if digits.is_empty() {
+        digits.push('0');
+    }
+    if is_negative {
+        digits.insert(0, '-');
+    }
+    Ok(digits)
+}
+
+/// Copies fields `fields` of tuple `data` into a new MsgPack array, see [Tuple::project()](struct.Tuple.html#method.project).
+pub(crate) fn project_fields(data: &[u8], fields: &[u32]) -> Result<Vec<u8>, Error> {
+    let mut cur = Cursor::new(data);
+    let field_count = rmp::decode::read_array_len(&mut cur)?;
+    let scan_count = fields
+        .iter()
+        .max()
+        .map_or(0, |fieldno| fieldno + 1)
+        .min(field_count);
+
+    // fields after the last projected one are not even skipped
+    let mut spans = Vec::with_capacity(scan_count as usize);
+    for _ in 0..scan_count {
+        let start = cur.position() as usize;
+        skip_msgpack(&mut cur)?;
+        spans.push(start..cur.position() as usize);
+    }
+
+    let mut result = Vec::with_capacity(data.len());
+    rmp::encode::write_array_len(&mut result, fields.len() as u32)?;
+    for fieldno in fields {
+        match spans.get(*fieldno as usize) {
+            Some(span) => result.extend_from_slice(&data[span.clone()]),
+            None => rmp::encode::write_nil(&mut result)?,
+        }
+    }
+    Ok(result)
+}
+
+/// Skips one MsgPack value (including nested values) in `cur`.
+pub(crate) fn skip_msgpack(cur: &mut (impl Read + Seek)) -> Result<(), Error> {
+    match rmp::decode::read_marker(cur)? {
+        Marker::FixPos(_) | Marker::FixNeg(_) | Marker::Null | Marker::True | Marker::False => {}
+        Marker::U8 | Marker::I8 => {
+            cur.seek(SeekFrom::Current(1))?;
+        }
+        Marker::U16 | Marker::I16 => {
+            cur.seek(SeekFrom::Current(2))?;
+        }
+        Marker::U32 | Marker::I32 | Marker::F32 => {
+            cur.seek(SeekFrom::Current(4))?;
+        }
+        Marker::U64 | Marker::I64 | Marker::F64 => {
+            cur.seek(SeekFrom::Current(8))?;
+        }
+        Marker::FixStr(len) => {
+            cur.seek(SeekFrom::Current(len as i64))?;
+        }
+        Marker::Str8 | Marker::Bin8 => {
+            let len = cur.read_u8()?;
+            cur.seek(SeekFrom::Current(len as i64))?;
+        }
+        Marker::Str16 | Marker::Bin16 => {
+            let len = cur.read_u16::<BigEndian>()?;
+            cur.seek(SeekFrom::Current(len as i64))?;
+        }
+        Marker::Str32 | Marker::Bin32 => {
+            let len = cur.read_u32::<BigEndian>()?;
+            cur.seek(SeekFrom::Current(len as i64))?;
+        }
+        Marker::FixArray(len) => {
+            for _ in 0..len {
+                skip_msgpack(cur)?;
+            }
+        }
+        Marker::Array16 => {
+            let len = cur.read_u16::<BigEndian>()?;
+            for _ in 0..len {
+                skip_msgpack(cur)?;
+            }
+        }
+        Marker::Array32 => {
+            let len = cur.read_u32::<BigEndian>()?;
+            for _ in 0..len {
+                skip_msgpack(cur)?;
+            }
+        }
+        Marker::FixMap(len) => {
+            let len = len * 2;
+            for _ in 0..len {
+                skip_msgpack(cur)?;
+            }
+        }
+        Marker::Map16 => {
+            let len = cur.read_u16::<BigEndian>()? * 2;
+            for _ in 0..len {
+                skip_msgpack(cur)?;
+            }
+        }
+        Marker::Map32 => {
+            let len = cur.read_u32::<BigEndian>()? * 2;
+            for _ in 0..len {
+                skip_msgpack(cur)?;
+            }
+        }
+        Marker::FixExt1 => {
+            cur.seek(SeekFrom::Current(2))?;
+        }
+        Marker::FixExt2 => {
+            cur.seek(SeekFrom::Current(3))?;
+        }
+        Marker::FixExt4 => {
+            cur.seek(SeekFrom::Current(5))?;
+        }
+        Marker::FixExt8 => {
+            cur.seek(SeekFrom::Current(9))?;
+        }
+        Marker::FixExt16 => {
+            cur.seek(SeekFrom::Current(17))?;
+        }
+        Marker::Ext8 => {
+            let len = cur.read_u8()?;
+            cur.seek(SeekFrom::Current(len as i64 + 1))?;
+        }
+        Marker::Ext16 => {
+            let len = cur.read_u16::<BigEndian>()?;
+            cur.seek(SeekFrom::Current(len as i64 + 1))?;
+        }
+        Marker::Ext32 => {
+            let len = cur.read_u32::<BigEndian>()?;
+            cur.seek(SeekFrom::Current(len as i64 + 1))?;
+        }
+        Marker::Reserved => {
+            return Err(rmp::decode::ValueReadError::TypeMismatch(Marker::Reserved).into())
+        }
+    }
+    Ok(())
+}
+
+/// Deserializes tuple contents keeping track of the field being decoded.
+/// On failure the error refers to this field.
+pub(crate) fn decode_fields<R, T>(reader: R) -> Result<T, Error>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let fieldno = Cell::new(None);
+    let names = Cell::new(&[][..]);
+    let mut deserializer = rmp_serde::Deserializer::new(reader);
+    T::deserialize(FieldTracker {
+        inner: &mut deserializer,
+        fieldno: &fieldno,
+        names: &names,
+    })
+    .map_err(|error| match fieldno.get() {
+        Some(fieldno) => FieldDecodeError {
+            fieldno: fieldno + 1,
+            name: names.get().get(fieldno as usize).copied(),
+            error,
+        }
+        .into(),
+        None => error.into(),
+    })
+}
+
+/// Wraps deserializer of the whole tuple and intercepts access to its fields
+struct FieldTracker<'f, D> {
+    inner: D,
+    fieldno: &'f Cell<Option<u32>>,
+    names: &'f Cell<&'static [&'static str]>,
+}
+
+impl<'f, D> FieldTracker<'f, D> {
+    fn visitor<V>(&self, inner: V) -> FieldTracker<'f, V> {
+        FieldTracker {
+            inner,
+            fieldno: self.fieldno,
+            names: self.names,
+        }
+    }
+}
+
+macro_rules! forward_to_inner {
+    ($($method:ident($($arg:ident: $ty:ty),*))*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.inner.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'f, D> Deserializer<'de> for FieldTracker<'f, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_any(visitor)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.names.set(fields);
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_struct(name, fields, visitor)
+    }
+
+    forward_to_inner! {
+        deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32() deserialize_i64()
+        deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64()
+        deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string()
+        deserialize_bytes() deserialize_byte_buf() deserialize_option() deserialize_unit()
+        deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str)
+        deserialize_map()
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier() deserialize_ignored_any()
+    }
+}
+
+impl<'de, 'f, V> Visitor<'de> for FieldTracker<'f, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let seq = FieldTracker {
+            inner: (seq, 0),
+            fieldno: self.fieldno,
+            names: self.names,
+        };
+        self.inner.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.inner.visit_map(map)
+    }
+}
+
+impl<'de, 'f, A> SeqAccess<'de> for FieldTracker<'f, (A, u32)>
+where
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let (seq, fieldno) = &mut self.inner;
+        self.fieldno.set(Some(*fieldno));
+        let result = seq.next_element_seed(seed)?;
+        match result {
+            Some(_) => *fieldno += 1,
+            None => self.fieldno.set(None),
+        };
+        Ok(result)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.0.size_hint()
+    }
+}
+
+macro_rules! forward_to_raw_data {
+    ($($method:ident($($arg:ident: $ty:ty),*))*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                let mut deserializer = rmp_serde::Deserializer::from_read_ref(self.as_raw_bytes());
+                (&mut deserializer).$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+/// Deserializes tuple contents in place: `T::deserialize(&tuple)`.
+///
+/// Unlike [as_struct()](struct.Tuple.html#method.as_struct), the data is not copied, so `T` may borrow strings and
+/// byte slices from the tuple.
+impl<'de> Deserializer<'de> for &'de Tuple {
+    type Error = rmp_serde::decode::Error;
+
+    forward_to_raw_data! {
+        deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32() deserialize_i64()
+        deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64()
+        deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string()
+        deserialize_bytes() deserialize_byte_buf() deserialize_option() deserialize_unit()
+        deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str)
+        deserialize_seq() deserialize_tuple(len: usize)
+        deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_map()
+        deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier() deserialize_ignored_any()
+    }
+}
+
+/// Serializes tuple contents as a MsgPack array, e.g. when a tuple is nested into another serialized value.
+///
+/// The contents are passed to the serializer value by value, strings and binaries are borrowed from the tuple.
+/// MsgPack extensions (decimals, UUIDs etc.) are passed as rmp-serde `_ExtStruct((type, bytes))`, so they are
+/// written back as is by MsgPack serializers.
+impl Serialize for Tuple {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        RawValue::deserialize(self)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+/// Decoded MsgPack value borrowing strings and binaries from the tuple, used to re-serialize tuple contents.
+enum RawValue<'a> {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    F32(f32),
+    F64(f64),
+    Str(&'a str),
+    Bin(&'a [u8]),
+    Ext(i8, &'a [u8]),
+    Array(Vec<RawValue<'a>>),
+    Map(Vec<(RawValue<'a>, RawValue<'a>)>),
+}
+
+impl<'de> Deserialize<'de> for RawValue<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RawValueVisitor;
+
+        impl<'de> Visitor<'de> for RawValueVisitor {
+            type Value = RawValue<'de>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("any MsgPack value")
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(RawValue::Nil)
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(RawValue::Bool(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(RawValue::Int(v))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(RawValue::UInt(v))
+            }
+
+            fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E> {
+                Ok(RawValue::F32(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(RawValue::F64(v))
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(RawValue::Str(v))
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+                Ok(RawValue::Bin(v))
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let (ext_type, data) = <(i8, &'de [u8])>::deserialize(deserializer)?;
+                Ok(RawValue::Ext(ext_type, data))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(RawValue::Array(items))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(RawValue::Map(entries))
+            }
+        }
+
+        deserializer.deserialize_any(RawValueVisitor)
+    }
+}
+
+impl Serialize for RawValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            RawValue::Nil => serializer.serialize_unit(),
+            RawValue::Bool(v) => serializer.serialize_bool(*v),
+            RawValue::Int(v) => serializer.serialize_i64(*v),
+            RawValue::UInt(v) => serializer.serialize_u64(*v),
+            RawValue::F32(v) => serializer.serialize_f32(*v),
+            RawValue::F64(v) => serializer.serialize_f64(*v),
+            RawValue::Str(v) => serializer.serialize_str(v),
+            RawValue::Bin(v) => serializer.serialize_bytes(v),
+            RawValue::Ext(ext_type, data) => serializer.serialize_newtype_struct(
+                rmp_serde::MSGPACK_EXT_STRUCT_NAME,
+                &(ext_type, RawValue::Bin(data)),
+            ),
+            RawValue::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            RawValue::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
 #[repr(C)]
 pub struct FunctionCtx {
     inner: *mut ffi::BoxFunctionCtx,
@@ -12,6 +12,7 @@ box.once('bootstrap_tests', function()
     box.schema.user.grant('test_user', 'read,write,execute,create,drop', 'universe')
 
     box.schema.sequence.create('test_seq')
+    box.schema.sequence.create('test_seq_custom', { start = 10, min = 5, max = 100, step = 3, cycle = true })
 
     box.schema.func.create('test_stored_proc')
     box.schema.func.create('test_schema_update')
@@ -26,6 +27,13 @@ function test_timeout()
     fiber.sleep(1.5)
 end
 
+function test_chained_error()
+    local cause = box.error.new({ code = 1001, type = 'InnerError', reason = 'inner failure' })
+    local err = box.error.new({ code = 1002, type = 'OuterError', reason = 'outer failure' })
+    err:set_prev(cause)
+    box.error(err)
+end
+
 function test_schema_update()
     box.schema.space.create('test_s_tmp')
 end
@@ -83,7 +83,7 @@ impl TDynBenchFn for BulkInsertBenchmark {
             }
 
             for (_, fiber) in fiber_pool.iter().enumerate() {
-                fiber.join();
+                fiber.join().unwrap();
             }
 
             print!(".");
@@ -0,0 +1,107 @@
+use std::io;
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+use tester::{Bencher, TDynBenchFn};
+
+use tarantool::index::{IndexFieldType, IndexOptions, IndexPart, IndexType, IteratorType};
+use tarantool::space::{Space, SpaceCreateOptions};
+use tarantool::tuple::{AsTuple, Tuple};
+
+pub struct SelectBenchmark {
+    pub num_rows: u32,
+    pub stream: bool,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub id: u32,
+    pub text: String,
+}
+
+impl AsTuple for Record {}
+
+fn prepare_space(num_rows: u32) -> Space {
+    if let Some(space) = Space::find("bench_select") {
+        return space;
+    }
+
+    let mut space = Space::create("bench_select", &SpaceCreateOptions::default()).unwrap();
+    let index_opts = IndexOptions {
+        index_type: Some(IndexType::Tree),
+        parts: Some(vec![IndexPart::new(1, IndexFieldType::Unsigned)]),
+        ..IndexOptions::default()
+    };
+    space.create_index("primary", &index_opts).unwrap();
+
+    for id in 0..num_rows {
+        space
+            .insert(&Record {
+                id,
+                text: format!("text_{}", id),
+            })
+            .unwrap();
+    }
+    space
+}
+
+impl TDynBenchFn for SelectBenchmark {
+    fn run(&self, harness: &mut Bencher) {
+        let space = prepare_space(self.num_rows);
+
+        harness.iter(|| {
+            let mut count = 0;
+            if self.stream {
+                for record in space
+                    .select_stream::<Record, _>(IteratorType::All, &())
+                    .unwrap()
+                {
+                    record.unwrap();
+                    count += 1;
+                }
+            } else {
+                for tuple in space.select(IteratorType::All, &()).unwrap() {
+                    tuple.into_struct::<Record>().unwrap();
+                    count += 1;
+                }
+            }
+            assert_eq!(count, self.num_rows);
+
+            print!(".");
+            io::stdout().flush().unwrap();
+        });
+    }
+}
+
+pub struct SelectIntoBenchmark {
+    pub num_rows: u32,
+    pub reuse_buffer: bool,
+}
+
+impl TDynBenchFn for SelectIntoBenchmark {
+    fn run(&self, harness: &mut Bencher) {
+        let space = prepare_space(self.num_rows);
+        let mut buf = Vec::<Tuple>::new();
+
+        // many small selects, where the allocation of the result takes a noticeable part
+        harness.iter(|| {
+            for id in (0..self.num_rows).step_by(10) {
+                let count = if self.reuse_buffer {
+                    space
+                        .select_into(IteratorType::Eq, &(id,), &mut buf)
+                        .unwrap()
+                } else {
+                    space
+                        .select(IteratorType::Eq, &(id,))
+                        .unwrap()
+                        .collect::<Vec<_>>()
+                        .len()
+                };
+                assert_eq!(count, 1);
+            }
+
+            print!(".");
+            io::stdout().flush().unwrap();
+        });
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use tarantool::tuple::AsTuple;
 
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct S1Record {
     pub id: u32,
     pub text: String,
@@ -14,16 +14,22 @@ use tarantool::index::{IndexFieldType, IndexOptions, IndexPart, IndexType};
 use tarantool::space::{Space, SpaceCreateOptions, SpaceFieldFormat, SpaceFieldType};
 
 mod bench_bulk_insert;
+mod bench_select;
 mod common;
 mod test_box;
+mod test_channel;
 mod test_coio;
 mod test_error;
 mod test_fiber;
 mod test_latch;
 mod test_log;
+mod test_mutex;
 mod test_net_box;
+mod test_once;
 mod test_raft;
+mod test_semaphore;
 mod test_session;
+mod test_shutdown;
 mod test_transaction;
 mod test_tuple;
 
@@ -158,43 +164,124 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
             options: Options::new(),
         },
         if cfg.bench {
-            vec![TestDescAndFn {
-                desc: TestDesc {
-                    name: TestName::StaticTestName("bench_bulk_insert"),
-                    ignore: false,
-                    should_panic: ShouldPanic::No,
-                    allow_fail: false,
-                    test_type: TestType::Unknown,
+            vec![
+                TestDescAndFn {
+                    desc: TestDesc {
+                        name: TestName::StaticTestName("bench_bulk_insert"),
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        allow_fail: false,
+                        test_type: TestType::Unknown,
+                    },
+                    testfn: TestFn::DynBenchFn(Box::new(bench_bulk_insert::BulkInsertBenchmark {
+                        test_size: 64,
+                        num_fibers: 256,
+                        num_rows: 1000,
+                    })),
+                },
+                TestDescAndFn {
+                    desc: TestDesc {
+                        name: TestName::StaticTestName("bench_select"),
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        allow_fail: false,
+                        test_type: TestType::Unknown,
+                    },
+                    testfn: TestFn::DynBenchFn(Box::new(bench_select::SelectBenchmark {
+                        num_rows: 100_000,
+                        stream: false,
+                    })),
                 },
-                testfn: TestFn::DynBenchFn(Box::new(bench_bulk_insert::BulkInsertBenchmark {
-                    test_size: 64,
-                    num_fibers: 256,
-                    num_rows: 1000,
-                })),
-            }]
+                TestDescAndFn {
+                    desc: TestDesc {
+                        name: TestName::StaticTestName("bench_select_stream"),
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        allow_fail: false,
+                        test_type: TestType::Unknown,
+                    },
+                    testfn: TestFn::DynBenchFn(Box::new(bench_select::SelectBenchmark {
+                        num_rows: 100_000,
+                        stream: true,
+                    })),
+                },
+                TestDescAndFn {
+                    desc: TestDesc {
+                        name: TestName::StaticTestName("bench_select_collect"),
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        allow_fail: false,
+                        test_type: TestType::Unknown,
+                    },
+                    testfn: TestFn::DynBenchFn(Box::new(bench_select::SelectIntoBenchmark {
+                        num_rows: 100_000,
+                        reuse_buffer: false,
+                    })),
+                },
+                TestDescAndFn {
+                    desc: TestDesc {
+                        name: TestName::StaticTestName("bench_select_into"),
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        allow_fail: false,
+                        test_type: TestType::Unknown,
+                    },
+                    testfn: TestFn::DynBenchFn(Box::new(bench_select::SelectIntoBenchmark {
+                        num_rows: 100_000,
+                        reuse_buffer: true,
+                    })),
+                },
+            ]
         } else {
             tests![
                 test_fiber::test_fiber_new,
                 test_fiber::test_fiber_new_with_attr,
+                test_fiber::test_fiber_builder,
+                test_fiber::test_fiber_stack_size,
+                test_fiber::test_fiber_yields,
+                test_fiber::test_fiber_spawn_with_context,
                 test_fiber::test_fiber_arg,
+                test_fiber::test_fiber_panic,
                 test_fiber::test_fiber_cancel,
                 test_fiber::test_fiber_wake,
                 test_fiber::test_fiber_cond_signal,
                 test_fiber::test_fiber_cond_broadcast,
+                test_fiber::test_fiber_cond_signal_fifo,
+                test_fiber::test_fiber_cond_broadcast_all,
                 test_fiber::test_fiber_cond_timeout,
                 test_box::test_space_get_by_name,
                 test_box::test_space_get_system,
                 test_box::test_index_get_by_name,
+                test_box::test_index_try_get_by_name,
+                test_box::test_space_index_debug,
                 test_box::test_box_insert,
+                test_box::test_box_insert_tuple,
                 test_box::test_box_replace,
                 test_box::test_box_delete,
                 test_box::test_box_update,
+                test_box::test_box_update_index_base,
                 test_box::test_box_upsert,
+                test_box::test_box_modify,
                 test_box::test_box_truncate,
                 test_box::test_box_get,
                 test_box::test_box_select,
+                test_box::test_box_select_unsupported_iterator,
+                test_box::test_box_select_tuple_key,
+                test_box::test_box_select_map,
+                test_box::test_box_select_grouped,
+                test_box::test_box_select_counted,
+                test_box::test_box_select_sorted_by,
+                test_box::test_box_verify_indexes,
+                test_box::test_box_select_into_capped,
+                test_box::test_box_select_range,
+                test_box::test_box_select_project,
+                test_box::test_box_select_stream,
+                test_box::test_box_select_scalar_key,
+                test_box::test_box_select_filter,
+                test_box::test_box_cursor,
                 test_box::test_box_select_composite_key,
                 test_box::test_box_len,
+                test_box::test_box_bsize,
                 test_box::test_box_random,
                 test_box::test_box_min_max,
                 test_box::test_box_count,
@@ -202,46 +289,108 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 test_box::test_box_sequence_get_by_name,
                 test_box::test_box_sequence_iterate,
                 test_box::test_box_sequence_set,
+                test_box::test_box_sequence_all,
                 test_box::test_space_create_opt_default,
                 test_box::test_space_create_opt_if_not_exists,
+                test_box::test_space_create_exists_error_code,
+                test_box::test_space_ensure,
+                test_box::test_space_cdc,
+                test_box::test_space_on_replace_txn,
                 test_box::test_space_create_id_increment,
                 test_box::test_space_create_opt_user,
                 test_box::test_space_create_opt_id,
+                test_box::test_space_create_opt_id_taken,
+                test_box::test_space_create_foreign_key,
+                test_box::test_space_create_for,
+                test_box::test_space_reset,
                 test_box::test_space_drop,
                 test_box::test_index_create_drop,
                 test_tuple::test_tuple_new_from_struct,
+                test_tuple::test_tuple_new_not_an_array,
                 test_tuple::test_tuple_field_count,
                 test_tuple::test_tuple_size,
+                test_tuple::test_tuple_byte_size,
                 test_tuple::test_tuple_into_struct,
+                test_tuple::test_tuple_into_struct_field_error,
+                test_tuple::test_tuple_deserialize,
+                test_tuple::test_tuple_to_json,
+                test_tuple::test_tuple_serialize_ext,
                 test_tuple::test_tuple_clone,
+                test_tuple::test_tuple_into_raw_from_raw,
+                test_tuple::test_tuple_as_raw_bytes,
+                test_tuple::test_tuple_raw_fields,
                 test_tuple::test_tuple_iterator,
                 test_tuple::test_tuple_iterator_seek_rewind,
                 test_tuple::test_tuple_get_format,
+                test_tuple::test_tuple_new_with_format,
+                test_tuple::test_tuple_apply_ops_returning,
                 test_tuple::test_tuple_get_field,
                 test_tuple::test_tuple_compare,
                 test_tuple::test_tuple_compare_with_key,
                 test_error::test_error_last,
+                test_error::test_error_as_tarantool,
+                test_error::test_error_ensure,
                 test_coio::test_coio_accept,
                 test_coio::test_coio_read_write,
                 test_coio::test_coio_call,
+                test_coio::test_coio_call_closure,
+                test_coio::test_coio_read_errno,
+                test_coio::test_coio_vectored_io,
+                test_coio::test_coio_read_until_deadline,
+                test_coio::test_coio_connect_deadline,
                 test_transaction::test_transaction_commit,
                 test_transaction::test_transaction_rollback,
+                test_transaction::test_transaction_iterator_ended,
                 test_log::test_log,
+                test_log::test_log_file_line,
                 test_latch::test_latch_lock,
                 test_latch::test_latch_try_lock,
+                test_mutex::test_mutex_lock,
+                test_mutex::test_condvar_bounded_queue,
+                test_mutex::test_condvar_wait_timeout,
+                test_semaphore::test_semaphore_concurrency,
+                test_semaphore::test_semaphore_try_acquire,
+                test_shutdown::test_shutdown_barrier,
+                test_shutdown::test_shutdown_barrier_timeout,
+                test_once::test_once_concurrent,
+                test_channel::test_channel_send_recv,
+                test_channel::test_channel_recv_timeout,
+                test_channel::test_channel_send_closed,
+                test_channel::test_channel_recv_closed,
+                test_channel::test_channel_close_wakes_receiver,
+                test_channel::test_channel_send_iter_recv_all,
+                test_channel::test_channel_send_iter_timeout,
+                test_channel::test_channel_split,
+                test_channel::test_channel_metrics,
+                test_channel::test_channel_priority_select,
+                test_channel::test_priority_channel,
                 test_net_box::test_immediate_close,
                 test_net_box::test_ping,
                 test_net_box::test_ping_timeout,
                 test_net_box::test_ping_concurrent,
                 test_net_box::test_call,
+                test_net_box::test_call_raw,
                 test_net_box::test_call_timeout,
+                test_net_box::test_request_timeout,
+                test_net_box::test_call_with_deadline,
                 test_net_box::test_eval,
+                test_net_box::test_eval_named,
+                test_net_box::test_tracer,
+                test_net_box::test_register_function,
+                test_net_box::test_remote_error_chain,
                 test_net_box::test_connection_error,
                 test_net_box::test_is_connected,
+                test_net_box::test_peer_version,
+                test_net_box::test_features_fallback,
+                test_net_box::test_features_id_error,
+                test_net_box::test_keepalive,
                 test_net_box::test_schema_sync,
                 test_net_box::test_select,
+                test_net_box::test_pipeline,
+                test_net_box::test_pipeline_encode_error,
                 test_net_box::test_get,
                 test_net_box::test_insert,
+                test_net_box::test_typed_ops,
                 test_net_box::test_replace,
                 test_net_box::test_update,
                 test_net_box::test_upsert,
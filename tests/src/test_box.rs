@@ -1,9 +1,26 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::ops::Bound;
+use std::rc::Rc;
+use std::time::Duration;
+
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use tarantool::index::{IndexOptions, IteratorType};
+use tarantool::error::{Error, TarantoolErrorCode};
+use tarantool::index::{
+    IndexBase, IndexFieldType, IndexOptions, IndexPart, IndexRange, IndexSequenceOption, IndexType,
+    IteratorType,
+};
 use tarantool::sequence::Sequence;
-use tarantool::space::{Space, SpaceCreateOptions, SpaceFieldFormat, SpaceFieldType, SystemSpace};
-use tarantool::tuple::Tuple;
+use tarantool::space::cdc::{Backpressure, ChangeOp};
+use tarantool::space::{
+    Space, SpaceCreateOptions, SpaceFieldForeignKey, SpaceFieldFormat, SpaceFieldType, SystemSpace,
+};
+use tarantool::space_format;
+use tarantool::transaction::start_transaction;
+use tarantool::tuple::{AsTuple, Tuple};
+use tarantool::tuple_key;
 
 use crate::common::{QueryOperation, S1Record, S2Key, S2Record};
 
@@ -23,6 +40,35 @@ pub fn test_index_get_by_name() {
     assert!(space.index("idx_1_invalid").is_none());
 }
 
+pub fn test_index_try_get_by_name() {
+    let space = Space::find("test_s2").unwrap();
+    assert!(space.try_index("idx_1").is_ok());
+
+    let err = space.try_index("idx_typo").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Tarantool error: NoSuchIndexName: No index 'idx_typo' in space 'test_s2' \
+         (available: primary, idx_1, idx_2, idx_3)"
+    );
+}
+
+pub fn test_space_index_debug() {
+    let space = Space::find("test_s2").unwrap();
+    assert_eq!(
+        format!("{:?}", space),
+        format!("Space {{ id: {}, name: \"test_s2\" }}", space.id())
+    );
+
+    let index = space.index("idx_1").unwrap();
+    assert_eq!(
+        format!("{:?}", index),
+        format!(
+            "Index {{ id: 1, name: \"idx_1\", space_id: {} }}",
+            space.id()
+        )
+    );
+}
+
 pub fn test_box_get() {
     let space = Space::find("test_s2").unwrap();
 
@@ -130,6 +176,401 @@ pub fn test_box_select() {
     );
 }
 
+tuple_key! {
+    struct S2PrefixKey {
+        id: u32,
+        a: i32,
+    }
+}
+
+pub fn test_box_select_tuple_key() {
+    let space = Space::find("test_s2").unwrap();
+    let idx_2 = space.index("idx_2").unwrap();
+
+    // two-part lookup by a prefix of (id, a, b)
+    let by_struct: Vec<S2Record> = idx_2
+        .select(IteratorType::Eq, &S2PrefixKey { id: 17, a: 2 })
+        .unwrap()
+        .map(|x| x.into_struct().unwrap())
+        .collect();
+    let by_tuple: Vec<S2Record> = idx_2
+        .select(IteratorType::Eq, &(17, 2))
+        .unwrap()
+        .map(|x| x.into_struct().unwrap())
+        .collect();
+    assert_eq!(by_struct.len(), 1);
+    assert_eq!(by_struct, by_tuple);
+}
+
+pub fn test_box_select_unsupported_iterator() {
+    let space = Space::find("test_s2").unwrap();
+
+    // idx_1 is a HASH index
+    let idx = space.index("idx_1").unwrap();
+    match idx.select(IteratorType::GE, &("key_1",)) {
+        Err(err @ Error::UnsupportedIterator { .. }) => {
+            assert_eq!(err.to_string(), "Hash index doesn't support GE iterator")
+        }
+        _ => panic!("UnsupportedIterator expected"),
+    }
+    assert!(idx.select(IteratorType::Eq, &("key_1",)).is_ok());
+}
+
+pub fn test_box_select_map() {
+    let space = Space::find("test_s2").unwrap();
+    let result: HashMap<u32, S2Record> = space.select_map(IteratorType::LE, &(5,), 0).unwrap();
+    assert_eq!(result.len(), 5);
+    for id in 1..=5 {
+        let record = &result[&id];
+        assert_eq!(record.id, id);
+        assert_eq!(record.key, format!("key_{}", id));
+    }
+
+    // duplicates: last wins
+    let idx = space.index("idx_3").unwrap();
+    let result: HashMap<i32, S1Record> = idx.select_map(IteratorType::Eq, &(3,), 3).unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[&3].id, 18);
+}
+
+pub fn test_box_select_grouped() {
+    let space = Space::find("test_s2").unwrap();
+    let result: HashMap<i32, Vec<S2Record>> =
+        space.select_grouped(IteratorType::All, &(), 3).unwrap();
+    assert_eq!(result.len(), 5);
+    for (a, group) in result.iter() {
+        assert_eq!(group.len(), 4);
+        assert!(group.iter().all(|r| r.a == *a));
+    }
+    assert_eq!(
+        result[&3].iter().map(|r| r.id).collect::<Vec<_>>(),
+        vec![3, 8, 13, 18]
+    );
+
+    // groups keep the iterator order
+    let result: HashMap<i32, Vec<S2Record>> =
+        space.select_grouped(IteratorType::LE, &(10,), 3).unwrap();
+    assert_eq!(
+        result[&0].iter().map(|r| r.id).collect::<Vec<_>>(),
+        vec![10, 5]
+    );
+    assert_eq!(result[&1].len(), 2);
+}
+
+pub fn test_box_select_counted() {
+    let space = Space::find("test_s2").unwrap();
+
+    let iter = space.select_counted(IteratorType::Eq, &(5,)).unwrap();
+    assert_eq!(iter.len(), 1);
+    let rows: Vec<Tuple> = iter.collect();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].field::<u32>(0).unwrap(), Some(5));
+
+    let iter = space.select_counted(IteratorType::Eq, &(100,)).unwrap();
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.count(), 0);
+
+    // non-unique index
+    let idx_3 = space.index("idx_3").unwrap();
+    let mut iter = idx_3.select_counted(IteratorType::Eq, &(3,)).unwrap();
+    assert_eq!(iter.len(), 4);
+    iter.next().unwrap();
+    assert_eq!(iter.len(), 3);
+    let mut ids = Vec::with_capacity(iter.len());
+    ids.extend(iter.map(|t| t.field::<u32>(0).unwrap().unwrap()));
+    assert_eq!(ids, vec![8, 13, 18]);
+}
+
+pub fn test_box_select_into_capped() {
+    let space = Space::find("test_s2").unwrap();
+    let mut buf = Vec::new();
+
+    let len = space
+        .select_into_capped(IteratorType::All, &(), 20, &mut buf)
+        .unwrap();
+    assert_eq!(len, 20);
+    assert_eq!(buf.len(), 20);
+
+    match space.select_into_capped(IteratorType::All, &(), 5, &mut buf) {
+        Err(Error::TooManyRows { max_rows }) => assert_eq!(max_rows, 5),
+        _ => panic!("expected TooManyRows error"),
+    }
+    assert!(buf.is_empty());
+
+    let idx_3 = space.index("idx_3").unwrap();
+    let len = idx_3
+        .select_into_capped(IteratorType::Eq, &(3,), 4, &mut buf)
+        .unwrap();
+    assert_eq!(len, 4);
+}
+
+pub fn test_box_select_range() {
+    let space = Space::find("test_s2").unwrap();
+    let ids = |range: IndexRange| {
+        range
+            .map(|t| t.field::<u32>(0).unwrap().unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let result = space
+        .range(Bound::Included(&(10,)), Bound::Excluded(&(20,)))
+        .unwrap();
+    assert_eq!(ids(result), (10..=19).collect::<Vec<_>>());
+
+    let result = space
+        .range(Bound::Excluded(&(17,)), Bound::Unbounded)
+        .unwrap();
+    assert_eq!(ids(result), vec![18, 19, 20]);
+
+    let result = space
+        .range(Bound::Unbounded, Bound::Included(&(3,)))
+        .unwrap();
+    assert_eq!(ids(result), vec![1, 2, 3]);
+
+    // multipart index with a partial key
+    let idx_2 = space.index("idx_2").unwrap();
+    let result = idx_2
+        .range(Bound::Included(&(5, 0)), Bound::Excluded(&(7, 2)))
+        .unwrap();
+    assert_eq!(ids(result), vec![5, 6]);
+}
+
+pub fn test_box_select_sorted_by() {
+    let space = Space::find("test_s2").unwrap();
+
+    // sort by a derived value, ties keep the index order
+    let rows = space
+        .select(IteratorType::All, &())
+        .unwrap()
+        .sorted_by(|row: &S2Record| row.id % 7)
+        .unwrap();
+    assert_eq!(rows.len(), 20);
+    let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+    assert_eq!(
+        ids,
+        vec![7, 14, 1, 8, 15, 2, 9, 16, 3, 10, 17, 4, 11, 18, 5, 12, 19, 6, 13, 20]
+    );
+
+    let idx_3 = space.index("idx_3").unwrap();
+    let rows = idx_3
+        .select(IteratorType::Eq, &(2,))
+        .unwrap()
+        .sorted_by(|row: &S2Record| std::cmp::Reverse(row.key.clone()))
+        .unwrap();
+    let keys: Vec<&str> = rows.iter().map(|row| row.key.as_str()).collect();
+    assert_eq!(keys, vec!["key_7", "key_2", "key_17", "key_12"]);
+}
+
+pub fn test_box_verify_indexes() {
+    // hash, multipart tree and non-unique tree secondary indexes
+    let space = Space::find("test_s2").unwrap();
+    let mismatches = space.verify_indexes().unwrap();
+    assert!(mismatches.is_empty());
+
+    let mut space = Space::create("new_space_18", &SpaceCreateOptions::default()).unwrap();
+    space
+        .create_index("primary", &IndexOptions::default())
+        .unwrap();
+    space
+        .create_index(
+            "secondary",
+            &IndexOptions {
+                index_type: Some(IndexType::Tree),
+                unique: Some(false),
+                parts: Some(vec![IndexPart::new(2, IndexFieldType::Unsigned)]),
+                ..IndexOptions::default()
+            },
+        )
+        .unwrap();
+    for id in 1..=5 {
+        space.insert(&(id, id * 10)).unwrap();
+    }
+
+    // bypass the secondary index: change its key field in the stored tuple data
+    let tuple = space.get(&(1,)).unwrap().unwrap();
+    assert_eq!(tuple.as_raw_bytes(), &[0x92, 0x01, 0x0a]);
+    let data = tuple.as_raw_bytes().as_ptr() as *mut u8;
+    unsafe { *data.add(2) = 99 };
+    let mismatches = space.verify_indexes().unwrap();
+    unsafe { *data.add(2) = 10 };
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].index_name, "secondary");
+    assert_eq!(mismatches[0].tuple.field::<u32>(0).unwrap(), Some(1));
+    assert!(space.verify_indexes().unwrap().is_empty());
+
+    // keys of JSON path and multikey indexes are not whole fields, so these indexes are skipped
+    space.truncate().unwrap();
+    for (name, path) in &[("by_path", "a"), ("multikey", "b[*]")] {
+        space
+            .create_index(
+                name,
+                &IndexOptions {
+                    index_type: Some(IndexType::Tree),
+                    unique: Some(false),
+                    parts: Some(vec![IndexPart {
+                        path: Some(path.to_string()),
+                        ..IndexPart::new(3, IndexFieldType::Unsigned)
+                    }]),
+                    ..IndexOptions::default()
+                },
+            )
+            .unwrap();
+    }
+    space
+        .insert(&(6, 60, serde_json::json!({"a": 1, "b": [2, 3]})))
+        .unwrap();
+    assert!(space.verify_indexes().unwrap().is_empty());
+
+    drop_space("new_space_18");
+}
+
+pub fn test_box_select_project() {
+    let mut space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    // a wide tuple: only fields 7 and 0 are decoded, the nested values around them are skipped
+    let row = serde_json::json!([
+        1,
+        "text",
+        {"nested": {"map": [1, 2, 3]}},
+        [[1.5, -2], ["deep", null]],
+        true,
+        null,
+        -100500,
+        "seventh",
+        {"k": "v"},
+        [1, 2]
+    ]);
+    space
+        .insert(&row.as_array().unwrap().iter().collect::<Vec<_>>())
+        .unwrap();
+
+    let result: Vec<(String, u32)> = space
+        .select(IteratorType::Eq, &(1,))
+        .unwrap()
+        .project(&[7, 0])
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(result, vec![("seventh".to_string(), 1)]);
+
+    let tuple = space.get(&(1,)).unwrap().unwrap();
+    let (value, missing): (i64, Option<u32>) = tuple.project(&[6, 42]).unwrap();
+    assert_eq!(value, -100500);
+    assert_eq!(missing, None);
+
+    // projected fields are still type-checked
+    assert!(tuple.project::<(String,)>(&[0]).is_err());
+    assert_eq!(tuple.project::<(bool,)>(&[4]).unwrap(), (true,));
+}
+
+pub fn test_box_select_stream() {
+    let space = Space::find("test_s2").unwrap();
+    let expected: Vec<S2Record> = space
+        .select(IteratorType::All, &())
+        .unwrap()
+        .map(|x| x.into_struct().unwrap())
+        .collect();
+    let result: Vec<S2Record> = space
+        .select_stream(IteratorType::All, &())
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(result.len(), 20);
+    assert_eq!(result, expected);
+
+    let idx = space.index("idx_3").unwrap();
+    let result: Vec<S1Record> = idx
+        .select_stream(IteratorType::Eq, &(3,))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        result.iter().map(|r| r.id).collect::<Vec<_>>(),
+        vec![3, 8, 13, 18]
+    );
+}
+
+pub fn test_box_select_scalar_key() {
+    let space = Space::find("test_s2").unwrap();
+    let record: S2Record = space.get(&5).unwrap().unwrap().into_struct().unwrap();
+    assert_eq!(record.id, 5);
+
+    let idx_1 = space.index("idx_1").unwrap();
+    let record: S2Record = idx_1.get(&"key_3").unwrap().unwrap().into_struct().unwrap();
+    assert_eq!(record.id, 3);
+
+    let idx_3 = space.index("idx_3").unwrap();
+    let ids: Vec<u32> = idx_3
+        .select(IteratorType::Eq, &3)
+        .unwrap()
+        .map(|x| x.into_struct::<S2Record>().unwrap().id)
+        .collect();
+    assert_eq!(ids, vec![3, 8, 13, 18]);
+    assert_eq!(idx_3.count(IteratorType::Eq, &3).unwrap(), 4);
+
+    let idx_2 = space.index("idx_2").unwrap();
+    let ids: Vec<u32> = idx_2
+        .select(IteratorType::Eq, &(3, 3))
+        .unwrap()
+        .map(|x| x.into_struct::<S2Record>().unwrap().id)
+        .collect();
+    assert_eq!(ids, vec![3]);
+}
+
+pub fn test_box_select_filter() {
+    let space = Space::find("test_s2").unwrap();
+    let result: Vec<S2Record> = space
+        .select(IteratorType::All, &())
+        .unwrap()
+        .filter(|t: &Tuple| t.field::<i32>(3).unwrap() == Some(2))
+        .map(|t| t.into_struct().unwrap())
+        .collect();
+    assert_eq!(
+        result.iter().map(|r| r.id).collect::<Vec<_>>(),
+        vec![2, 7, 12, 17]
+    );
+    assert!(result.iter().all(|r| r.a == 2));
+}
+
+pub fn test_box_cursor() {
+    let space = Space::find("test_s2").unwrap();
+    let id = |t: Option<Tuple>| t.map(|t| t.into_struct::<S2Record>().unwrap().id);
+
+    let mut cursor = space.cursor();
+    assert_eq!(id(cursor.prev()), None);
+    assert_eq!(id(cursor.next()), Some(1));
+    assert_eq!(id(cursor.next()), Some(2));
+    assert_eq!(id(cursor.next()), Some(3));
+    assert_eq!(id(cursor.prev()), Some(2));
+    assert_eq!(id(cursor.prev()), Some(1));
+    // no tuple before the first one: the cursor stays
+    assert_eq!(id(cursor.prev()), None);
+    assert_eq!(id(cursor.next()), Some(2));
+
+    // secondary index
+    let mut cursor = space.index("idx_2").unwrap().cursor();
+    assert_eq!(id(cursor.next()), Some(1));
+    let second = cursor.next().unwrap().into_struct::<S2Record>().unwrap();
+    assert_eq!(
+        cursor.key().unwrap().as_raw_bytes(),
+        Tuple::from_struct(&(second.id, second.a, second.b))
+            .unwrap()
+            .as_raw_bytes()
+    );
+    assert_eq!(id(cursor.prev()), Some(1));
+    assert_eq!(id(cursor.try_prev().unwrap()), None);
+    assert_eq!(id(cursor.try_next().unwrap()), Some(2));
+
+    // select errors are reported by try_next()
+    let mut cursor = space.index("idx_1").unwrap().cursor();
+    assert!(matches!(
+        cursor.try_next(),
+        Err(Error::UnsupportedIterator { .. })
+    ));
+    assert!(cursor.next().is_none());
+    assert!(cursor.key().is_none());
+}
+
 pub fn test_box_select_composite_key() {
     let space = Space::find("test_s2").unwrap();
     let idx = space.index("idx_2").unwrap();
@@ -156,6 +597,38 @@ pub fn test_box_len() {
     assert_eq!(space.len().unwrap(), 20 as usize);
 }
 
+pub fn test_box_bsize() {
+    let mut space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+    assert_eq!(space.len().unwrap(), 0);
+    assert_eq!(space.data_bsize().unwrap(), 0);
+    let empty_bsize = space.bsize().unwrap();
+
+    let mut data_size = 0;
+    for i in 0..100 {
+        let tuple = space
+            .insert(&S1Record {
+                id: i,
+                text: "x".repeat(100),
+            })
+            .unwrap()
+            .unwrap();
+        data_size += tuple.bsize();
+    }
+    assert_eq!(space.len().unwrap(), 100);
+    assert_eq!(space.data_bsize().unwrap(), data_size);
+    assert_eq!(space.scan_data_bsize().unwrap(), data_size);
+    assert_eq!(
+        space.index_bsize().unwrap(),
+        space.primary_key().bsize().unwrap()
+    );
+    assert_eq!(
+        space.bsize().unwrap(),
+        space.data_bsize().unwrap() + space.index_bsize().unwrap()
+    );
+    assert!(space.bsize().unwrap() > empty_bsize + data_size);
+}
+
 pub fn test_box_random() {
     let space = Space::find("test_s2").unwrap();
     let idx = space.primary_key();
@@ -252,6 +725,39 @@ pub fn test_box_insert() {
     assert_eq!(output.unwrap().into_struct::<S1Record>().unwrap(), input);
 }
 
+pub fn test_box_insert_tuple() {
+    let source = Space::find("test_s2").unwrap();
+    let mut target = Space::find("test_s1").unwrap();
+    target.truncate().unwrap();
+
+    let tuple = source.get(&(3,)).unwrap().unwrap();
+    let inserted = target.insert(&tuple).unwrap().unwrap();
+    assert_eq!(inserted.as_raw_bytes(), tuple.as_raw_bytes());
+
+    let copy = target.get(&(3,)).unwrap().unwrap();
+    assert_eq!(copy.as_raw_bytes(), tuple.as_raw_bytes());
+    assert_eq!(
+        copy.into_struct::<S2Record>().unwrap(),
+        tuple.as_struct::<S2Record>().unwrap()
+    );
+
+    let other = source.get(&(4,)).unwrap().unwrap();
+    let modified = other.apply_ops(&[QueryOperation {
+        op: "=".to_string(),
+        field_id: 1,
+        value: 3.into(),
+    }]);
+    let result = target.replace(&modified.unwrap()).unwrap().unwrap();
+    assert_eq!(result.field::<String>(1).unwrap().unwrap(), "key_4");
+
+    // a tuple nested into another value is serialized as its contents
+    let nested = Tuple::from_struct(&(1, &tuple)).unwrap();
+    assert_eq!(
+        nested.field::<S2Record>(1).unwrap().unwrap(),
+        tuple.as_struct::<S2Record>().unwrap()
+    );
+}
+
 pub fn test_box_replace() {
     let mut space = Space::find("test_s1").unwrap();
     space.truncate().unwrap();
@@ -339,6 +845,114 @@ pub fn test_box_update() {
     );
 }
 
+pub fn test_box_update_index_base() {
+    let mut space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    space.insert(&(1, "Original")).unwrap();
+    space.insert(&(2, "Original")).unwrap();
+
+    let one_based = space
+        .update_with_base(
+            &(1,),
+            &vec![QueryOperation {
+                op: "=".to_string(),
+                field_id: 2,
+                value: "New".into(),
+            }],
+            IndexBase::default(),
+        )
+        .unwrap()
+        .unwrap()
+        .into_struct::<S1Record>()
+        .unwrap();
+
+    let zero_based = space
+        .update_with_base(
+            &(2,),
+            &vec![QueryOperation {
+                op: "=".to_string(),
+                field_id: 1,
+                value: "New".into(),
+            }],
+            IndexBase::Zero,
+        )
+        .unwrap()
+        .unwrap()
+        .into_struct::<S1Record>()
+        .unwrap();
+
+    assert_eq!(IndexBase::default(), IndexBase::One);
+    assert_eq!(one_based.text, "New");
+    assert_eq!(zero_based.text, one_based.text);
+
+    // plain update() is zero-based
+    let plain = space
+        .update(
+            &(2,),
+            &vec![QueryOperation {
+                op: "=".to_string(),
+                field_id: 1,
+                value: "Plain".into(),
+            }],
+        )
+        .unwrap()
+        .unwrap()
+        .into_struct::<S1Record>()
+        .unwrap();
+    assert_eq!(plain.text, "Plain");
+}
+
+pub fn test_box_modify() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Counter {
+        id: u32,
+        name: String,
+        value: i64,
+    }
+    impl AsTuple for Counter {}
+
+    let mut space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+    space
+        .insert(&Counter {
+            id: 1,
+            name: "counter".to_string(),
+            value: 10,
+        })
+        .unwrap();
+
+    let result = space
+        .modify(&(1,), |counter: &mut Counter| counter.value += 5)
+        .unwrap();
+    let expected = Counter {
+        id: 1,
+        name: "counter".to_string(),
+        value: 15,
+    };
+    assert_eq!(result.as_ref(), Some(&expected));
+    let stored = space.get(&(1,)).unwrap().unwrap();
+    assert_eq!(stored.into_struct::<Counter>().unwrap(), expected);
+
+    // missing row: the closure is not called
+    let result = space
+        .modify(&(2,), |_: &mut Counter| panic!("must not be called"))
+        .unwrap();
+    assert_eq!(result, None);
+
+    // changing the primary key is rejected
+    let err = space
+        .modify(&(1,), |counter: &mut Counter| counter.id = 2)
+        .unwrap_err();
+    assert_eq!(
+        err.tarantool_code(),
+        Some(TarantoolErrorCode::IllegalParams)
+    );
+    assert_eq!(space.len().unwrap(), 1);
+    let stored = space.get(&(1,)).unwrap().unwrap();
+    assert_eq!(stored.into_struct::<Counter>().unwrap(), expected);
+}
+
 pub fn test_box_upsert() {
     let mut space = Space::find("test_s1").unwrap();
     space.truncate().unwrap();
@@ -429,6 +1043,30 @@ pub fn test_box_sequence_set() {
     assert_eq!(seq.next().unwrap(), 100);
 }
 
+pub fn test_box_sequence_all() {
+    let sequences = Sequence::all().unwrap();
+    let seq = sequences
+        .iter()
+        .find(|seq| seq.name() == "test_seq_custom")
+        .unwrap();
+    assert_eq!(seq.start(), 10);
+    assert_eq!(seq.min(), 5);
+    assert_eq!(seq.max(), 100);
+    assert_eq!(seq.step(), 3);
+    assert!(seq.cycle());
+
+    let default_seq = sequences
+        .iter()
+        .find(|seq| seq.name() == "test_seq")
+        .unwrap();
+    assert_eq!(default_seq.step(), 1);
+    assert!(!default_seq.cycle());
+
+    let by_id = Sequence::find_by_id(seq.id()).unwrap().unwrap();
+    assert_eq!(by_id.name(), "test_seq_custom");
+    assert!(Sequence::find_by_id(u32::MAX).unwrap().is_none());
+}
+
 pub fn test_space_create_opt_default() {
     let opts = SpaceCreateOptions::default();
 
@@ -455,6 +1093,218 @@ pub fn test_space_create_opt_if_not_exists() {
     drop_space("new_space_2");
 }
 
+pub fn test_space_create_exists_error_code() {
+    let opts = SpaceCreateOptions::default();
+    Space::create("new_space_14", &opts).unwrap();
+
+    let err = Space::create("new_space_14", &opts).map(drop).unwrap_err();
+    assert_eq!(err.tarantool_code(), Some(TarantoolErrorCode::SpaceExists));
+
+    // other failures have their own codes
+    let opts_unknown_user = SpaceCreateOptions {
+        user: Some("no_such_user".to_string()),
+        ..SpaceCreateOptions::default()
+    };
+    let err = Space::create("new_space_15", &opts_unknown_user)
+        .map(drop)
+        .unwrap_err();
+    assert_eq!(err.tarantool_code(), Some(TarantoolErrorCode::NoSuchUser));
+    assert!(Space::find("new_space_15").is_none());
+
+    drop_space("new_space_14");
+}
+
+pub fn test_space_ensure() {
+    let opts = SpaceCreateOptions {
+        format: Some(vec![
+            SpaceFieldFormat::new("id", SpaceFieldType::Unsigned),
+            SpaceFieldFormat::new("name", SpaceFieldType::String),
+        ]),
+        ..SpaceCreateOptions::default()
+    };
+    let created = Space::ensure("new_space_16", &opts).unwrap();
+    let reused = Space::ensure("new_space_16", &opts).unwrap();
+    assert_eq!(created.id(), reused.id());
+
+    // no format to check
+    let reused = Space::ensure("new_space_16", &SpaceCreateOptions::default()).unwrap();
+    assert_eq!(created.id(), reused.id());
+
+    let other_opts = SpaceCreateOptions {
+        format: Some(vec![
+            SpaceFieldFormat::new("id", SpaceFieldType::Unsigned),
+            SpaceFieldFormat::new("name", SpaceFieldType::Integer),
+        ]),
+        ..SpaceCreateOptions::default()
+    };
+    let err = Space::ensure("new_space_16", &other_opts)
+        .map(drop)
+        .unwrap_err();
+    assert_eq!(
+        err.tarantool_code(),
+        Some(TarantoolErrorCode::WrongSpaceFormat)
+    );
+    assert!(err.to_string().contains("field 'name'"));
+
+    drop_space("new_space_16");
+}
+
+pub fn test_space_cdc() {
+    let mut space = Space::create("new_space_17", &SpaceCreateOptions::default()).unwrap();
+    space
+        .create_index("primary", &IndexOptions::default())
+        .unwrap();
+
+    let changes = space.cdc(10, Backpressure::Block).unwrap();
+    space.insert(&(1, "a".to_string())).unwrap();
+    space.replace(&(1, "b".to_string())).unwrap();
+    space
+        .update(
+            &(1,),
+            &vec![QueryOperation {
+                op: "=".to_string(),
+                field_id: 1,
+                value: "c".into(),
+            }],
+        )
+        .unwrap();
+    space.delete(&(1,)).unwrap();
+
+    let events = changes.recv_all(10, Duration::from_secs(0)).unwrap();
+    let ops: Vec<ChangeOp> = events.iter().map(|e| e.op).collect();
+    assert_eq!(
+        ops,
+        vec![
+            ChangeOp::Insert,
+            ChangeOp::Replace,
+            ChangeOp::Update,
+            ChangeOp::Delete
+        ]
+    );
+    let text = |tuple: &Option<Tuple>| {
+        tuple
+            .as_ref()
+            .map(|t| t.field::<String>(1).unwrap().unwrap())
+    };
+    let values: Vec<(Option<String>, Option<String>)> = events
+        .iter()
+        .map(|e| (text(&e.old), text(&e.new)))
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            (None, Some("a".to_string())),
+            (Some("a".to_string()), Some("b".to_string())),
+            (Some("b".to_string()), Some("c".to_string())),
+            (Some("c".to_string()), None),
+        ]
+    );
+    drop(changes);
+
+    // events which don't fit into the channel are dropped
+    let changes = space.cdc(1, Backpressure::Drop).unwrap();
+    space.insert(&(2, "d".to_string())).unwrap();
+    space.insert(&(3, "e".to_string())).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes.dropped(), 1);
+    drop(changes);
+
+    // with Backpressure::Block the events which don't fit into the channel wait for the reader
+    let changes = space.cdc(2, Backpressure::Block).unwrap();
+    for id in 10..15 {
+        space.insert(&(id, "g".to_string())).unwrap();
+    }
+    assert_eq!(changes.len(), 2);
+    let ids: Vec<u32> = (0..5)
+        .map(|_| {
+            let event = changes.recv_timeout(Duration::from_secs(1)).unwrap();
+            event.new.unwrap().field::<u32>(0).unwrap().unwrap()
+        })
+        .collect();
+    assert_eq!(ids, vec![10, 11, 12, 13, 14]);
+    assert_eq!(changes.dropped(), 0);
+    drop(changes);
+
+    // the changes are sent on commit, rolled back changes are discarded
+    let changes = space.cdc(10, Backpressure::Drop).unwrap();
+    start_transaction(|| -> Result<(), Error> {
+        space.insert(&(20, "h".to_string()))?;
+        space.insert(&(21, "i".to_string()))?;
+        assert!(changes.is_empty());
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(changes.len(), 2);
+    start_transaction(|| -> Result<(), Error> {
+        space.insert(&(22, "j".to_string()))?;
+        Err(Error::IO(std::io::ErrorKind::Other.into()))
+    })
+    .unwrap_err();
+    assert_eq!(changes.len(), 2);
+    assert!(space.get(&(22,)).unwrap().is_none());
+    drop(changes);
+
+    // the trigger is removed with the stream
+    let changes = space.cdc(10, Backpressure::Drop).unwrap();
+    drop(changes);
+    space.insert(&(4, "f".to_string())).unwrap();
+
+    // a channel can't have zero capacity
+    assert!(matches!(
+        space.cdc(0, Backpressure::Drop).map(drop),
+        Err(Error::Tarantool(_))
+    ));
+
+    drop_space("new_space_17");
+}
+
+pub fn test_space_on_replace_txn() {
+    let mut space = Space::create("new_space_19", &SpaceCreateOptions::default()).unwrap();
+    space
+        .create_index("primary", &IndexOptions::default())
+        .unwrap();
+
+    let calls = Rc::new(Cell::new(0));
+    start_transaction(|| -> Result<(), Error> {
+        let calls = calls.clone();
+        space.on_replace_txn(move |old, new| {
+            if old.is_none() && new.is_some() {
+                calls.set(calls.get() + 1);
+            }
+        })?;
+        space.insert(&(1, "a".to_string()))?;
+        space.insert(&(2, "b".to_string()))?;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(calls.get(), 2);
+
+    // the trigger is removed on commit
+    space.insert(&(3, "c".to_string())).unwrap();
+    assert_eq!(calls.get(), 2);
+
+    // ... and on rollback
+    let result = start_transaction(|| -> Result<(), Error> {
+        let calls = calls.clone();
+        space.on_replace_txn(move |_, _| calls.set(calls.get() + 1))?;
+        space.insert(&(4, "d".to_string()))?;
+        Err(Error::IO(std::io::ErrorKind::Other.into()))
+    });
+    assert!(result.is_err());
+    assert_eq!(calls.get(), 3);
+    space.insert(&(5, "e".to_string())).unwrap();
+    assert_eq!(calls.get(), 3);
+
+    // there must be a transaction to bind the trigger to
+    let err = space.on_replace_txn(|_, _| {}).unwrap_err();
+    assert_eq!(
+        err.tarantool_code(),
+        Some(TarantoolErrorCode::NoTransaction)
+    );
+
+    drop_space("new_space_19");
+}
+
 pub fn test_space_create_id_increment() {
     let opts = SpaceCreateOptions::default();
     let _result = Space::create("new_space_3", &opts);
@@ -501,6 +1351,163 @@ pub fn test_space_create_opt_id() {
     drop_space("new_space_6");
 }
 
+pub fn test_space_create_opt_id_taken() {
+    let opts = SpaceCreateOptions {
+        id: Some(600),
+        ..SpaceCreateOptions::default()
+    };
+    let space = Space::create("new_space_10", &opts).unwrap();
+    assert_eq!(space.id(), 600);
+    assert_eq!(Space::find("new_space_10").unwrap().id(), 600);
+
+    // the id is taken
+    let err = Space::create("new_space_11", &opts).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Tarantool error: SpaceExists: new_space_11 (id 600 is taken by space 'new_space_10')"
+    );
+    assert!(Space::find("new_space_11").is_none());
+
+    drop_space("new_space_10");
+}
+
+pub fn test_space_create_foreign_key() {
+    let opts = SpaceCreateOptions {
+        is_local: false,
+        is_temporary: false,
+        ..SpaceCreateOptions::default()
+    };
+
+    let mut parent = Space::create(
+        "new_space_12",
+        &SpaceCreateOptions {
+            format: Some(vec![SpaceFieldFormat::new("id", SpaceFieldType::Unsigned)]),
+            ..opts.clone()
+        },
+    )
+    .unwrap();
+    parent
+        .create_index("primary", &IndexOptions::default())
+        .unwrap();
+
+    let parent_ref = SpaceFieldFormat {
+        foreign_key: Some(SpaceFieldForeignKey {
+            space: "new_space_12".to_string(),
+            field: "id".to_string(),
+        }),
+        ..SpaceFieldFormat::new("parent_id", SpaceFieldType::Unsigned)
+    };
+    let mut child = Space::create(
+        "new_space_13",
+        &SpaceCreateOptions {
+            format: Some(vec![
+                SpaceFieldFormat::new("id", SpaceFieldType::Unsigned),
+                parent_ref,
+            ]),
+            ..opts
+        },
+    )
+    .unwrap();
+    child
+        .create_index("primary", &IndexOptions::default())
+        .unwrap();
+
+    // no parent with id 1
+    match child.insert(&(1, 1)) {
+        Err(Error::Tarantool(e)) => {
+            assert_eq!(e.error_code(), TarantoolErrorCode::FieldForeignKeyFailed)
+        }
+        _ => panic!("foreign key violation expected"),
+    }
+    assert_eq!(child.len().unwrap(), 0);
+
+    parent.insert(&(1,)).unwrap();
+    child.insert(&(1, 1)).unwrap();
+
+    drop_space("new_space_13");
+    drop_space("new_space_12");
+}
+
+space_format! {
+    #[derive(Serialize, Deserialize)]
+    struct FormatRecord {
+        id: u32,
+        name: String,
+        comment: Option<String>,
+        score: f64,
+    }
+}
+
+pub fn test_space_create_for() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct FieldFormat {
+        name: String,
+        #[serde(rename = "type")]
+        field_type: String,
+        #[serde(default)]
+        is_nullable: bool,
+    }
+
+    let space =
+        Space::create_for::<FormatRecord>("new_space_8", &SpaceCreateOptions::default()).unwrap();
+
+    let sys_space: Space = SystemSpace::Space.into();
+    let format: Vec<FieldFormat> = sys_space
+        .get(&(space.id(),))
+        .unwrap()
+        .unwrap()
+        .field(6)
+        .unwrap()
+        .unwrap();
+    let field = |name: &str, field_type: &str, is_nullable| FieldFormat {
+        name: name.to_string(),
+        field_type: field_type.to_string(),
+        is_nullable,
+    };
+    assert_eq!(
+        format,
+        vec![
+            field("id", "Unsigned", false),
+            field("name", "String", false),
+            field("comment", "String", true),
+            field("score", "Double", false),
+        ]
+    );
+
+    drop_space("new_space_8");
+}
+
+pub fn test_space_reset() {
+    let mut space = Space::create("new_space_9", &SpaceCreateOptions::default()).unwrap();
+    assert!(space.sequence().unwrap().is_none());
+
+    let index_opts = IndexOptions {
+        sequence: Some(IndexSequenceOption::True),
+        ..IndexOptions::default()
+    };
+    space.create_index("primary", &index_opts).unwrap();
+    assert_eq!(space.sequence().unwrap().unwrap().name(), "new_space_9_seq");
+
+    let insert = |space: &mut Space| {
+        space
+            .insert(&(None::<u32>, "text"))
+            .unwrap()
+            .unwrap()
+            .field::<u32>(0)
+            .unwrap()
+            .unwrap()
+    };
+    assert_eq!(insert(&mut space), 1);
+    assert_eq!(insert(&mut space), 2);
+    assert_eq!(space.len().unwrap(), 2);
+
+    space.reset().unwrap();
+    assert_eq!(space.len().unwrap(), 0);
+    assert_eq!(insert(&mut space), 1);
+
+    drop_space("new_space_9");
+}
+
 pub fn test_space_drop() {
     let opts = SpaceCreateOptions::default();
 
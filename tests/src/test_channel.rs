@@ -0,0 +1,272 @@
+use std::time::Duration;
+
+use tarantool::fiber::channel::{
+    Channel, ChannelMetrics, PriorityChannel, RecvError, RecvTimeoutError, SendError,
+    SendTimeoutError, Sender,
+};
+use tarantool::fiber::{sleep, Fiber, PrioritySelect};
+
+pub fn test_channel_send_recv() {
+    let channel = Channel::new(1);
+
+    let mut fiber = Fiber::new("test_fiber", &mut |channel: Box<Channel<i32>>| {
+        for i in 0..3 {
+            channel.send(i).unwrap();
+        }
+        0
+    });
+    fiber.set_joinable(true);
+    fiber.start(channel.clone());
+
+    for i in 0..3 {
+        assert_eq!(channel.recv(), Ok(i));
+    }
+    fiber.join().unwrap();
+}
+
+pub fn test_channel_recv_timeout() {
+    let channel = Channel::<i32>::new(1);
+    assert_eq!(
+        channel.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+}
+
+pub fn test_channel_send_closed() {
+    let channel = Channel::new(10);
+    assert!(!channel.is_closed());
+    channel.close();
+    assert!(channel.is_closed());
+
+    match channel.send("value".to_string()) {
+        Err(SendError(value)) => assert_eq!(value, "value"),
+        Ok(_) => panic!("send succeeded on a closed channel"),
+    }
+    assert!(channel.is_empty());
+}
+
+pub fn test_channel_recv_closed() {
+    let channel = Channel::new(10);
+    channel.send(1).unwrap();
+    channel.send(2).unwrap();
+    channel.close();
+
+    assert_eq!(channel.len(), 2);
+    assert_eq!(channel.recv(), Ok(1));
+    assert_eq!(channel.recv(), Ok(2));
+    assert_eq!(channel.recv(), Err(RecvError));
+    assert_eq!(
+        channel.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Closed)
+    );
+}
+
+pub fn test_channel_close_wakes_receiver() {
+    let channel = Channel::<i32>::new(1);
+
+    let mut fiber = Fiber::new("test_fiber", &mut |channel: Box<Channel<i32>>| {
+        assert_eq!(channel.recv(), Err(RecvError));
+        0
+    });
+    fiber.set_joinable(true);
+    fiber.start(channel.clone());
+
+    sleep(0.01);
+    channel.close();
+    fiber.join().unwrap();
+}
+
+pub fn test_channel_send_iter_recv_all() {
+    let channel = Channel::new(16);
+
+    let mut fiber = Fiber::new("test_fiber", &mut |channel: Box<Channel<i32>>| {
+        let sent = channel.send_iter(0..50, Duration::from_secs(1)).unwrap();
+        assert_eq!(sent, 50);
+        channel.close();
+        0
+    });
+    fiber.set_joinable(true);
+    fiber.start(channel.clone());
+
+    let mut received = vec![];
+    while let Ok(chunk) = channel.recv_all(10, Duration::from_secs(1)) {
+        assert!(!chunk.is_empty() && chunk.len() <= 10);
+        received.extend(chunk);
+    }
+    fiber.join().unwrap();
+    assert_eq!(received, (0..50).collect::<Vec<_>>());
+}
+
+pub fn test_channel_send_iter_timeout() {
+    let channel = Channel::new(2);
+
+    let err = channel
+        .send_iter(0..5, Duration::from_millis(10))
+        .unwrap_err();
+    assert_eq!(err.sent, 2);
+    assert_eq!(err.error, SendTimeoutError::Timeout(2));
+    assert_eq!(err.rest.collect::<Vec<_>>(), vec![3, 4]);
+
+    assert_eq!(
+        channel.recv_all(10, Duration::from_millis(10)),
+        Ok(vec![0, 1])
+    );
+    assert_eq!(
+        channel.recv_all(10, Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+}
+
+pub fn test_channel_split() {
+    let (tx, rx) = Channel::new(2).split();
+
+    let mut producer = |tx: Box<Sender<i32>>| {
+        for i in 0..5 {
+            tx.send(i).unwrap();
+            sleep(0.001);
+        }
+        0
+    };
+    let mut fiber_1 = Fiber::new("test_fiber_1", &mut producer);
+    fiber_1.set_joinable(true);
+    fiber_1.start(tx.clone());
+
+    let mut producer = |tx: Box<Sender<i32>>| {
+        for i in 10..15 {
+            tx.send(i).unwrap();
+        }
+        0
+    };
+    let mut fiber_2 = Fiber::new("test_fiber_2", &mut producer);
+    fiber_2.set_joinable(true);
+    fiber_2.start(tx);
+
+    let mut received = vec![];
+    while let Ok(value) = rx.recv() {
+        received.push(value);
+    }
+    assert!(rx.is_closed());
+    received.sort_unstable();
+    assert_eq!(received, (0..5).chain(10..15).collect::<Vec<_>>());
+
+    fiber_1.join().unwrap();
+    fiber_2.join().unwrap();
+
+    // dropping the receiver closes the channel for senders
+    let (tx, rx) = Channel::new(1).split();
+    drop(rx);
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(1), Err(SendError(1)));
+}
+
+pub fn test_channel_priority_select() {
+    let data = Channel::new(10);
+    let control = Channel::new(10);
+    let mut select = PrioritySelect::new();
+    select.add(&data, 0).add(&control, 10);
+
+    // the high-priority channel is always drained first when both are ready
+    for i in 0..3 {
+        data.send(i).unwrap();
+        control.send(100 + i).unwrap();
+    }
+    let received = (0..6).map(|_| select.recv().unwrap()).collect::<Vec<_>>();
+    assert_eq!(received, vec![100, 101, 102, 0, 1, 2]);
+    assert_eq!(
+        select.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+
+    // a waiting fiber is woken up by a send into any of the channels
+    let mut sender = |_| {
+        sleep(0.01);
+        data.send(1).unwrap();
+        0
+    };
+    let mut fiber = Fiber::new("test_fiber", &mut sender);
+    fiber.set_joinable(true);
+    fiber.start(());
+    assert_eq!(select.recv(), Ok(1));
+    fiber.join().unwrap();
+
+    // fails when all channels are closed and empty
+    data.close();
+    control.send(300).unwrap();
+    control.close();
+    assert_eq!(select.recv(), Ok(300));
+    assert_eq!(select.recv(), Err(RecvError));
+}
+
+pub fn test_priority_channel() {
+    let channel = PriorityChannel::new(10);
+    channel.send(1).unwrap();
+    channel.send_urgent(101).unwrap();
+    channel.send(2).unwrap();
+    channel.send_urgent(102).unwrap();
+    channel.send(3).unwrap();
+    assert_eq!(channel.len(), 5);
+
+    let received = (0..5).map(|_| channel.recv().unwrap()).collect::<Vec<_>>();
+    assert_eq!(received, vec![101, 102, 1, 2, 3]);
+    assert!(channel.is_empty());
+
+    // a waiting receiver is woken up by an urgent send
+    let sender = channel.clone();
+    let mut fiber = Fiber::new("test_fiber", &mut |_| {
+        sleep(0.01);
+        sender.send_urgent(103).unwrap();
+        0
+    });
+    fiber.set_joinable(true);
+    fiber.start(());
+    assert_eq!(channel.recv(), Ok(103));
+    fiber.join().unwrap();
+
+    channel.send(4).unwrap();
+    channel.close();
+    assert_eq!(channel.recv(), Ok(4));
+    assert_eq!(channel.recv(), Err(RecvError));
+    assert_eq!(channel.send_urgent(104), Err(SendError(104)));
+}
+
+pub fn test_channel_metrics() {
+    let channel = Channel::new(2);
+    assert_eq!(
+        channel.metrics(),
+        ChannelMetrics {
+            capacity: 2,
+            len: 0,
+            sends_blocked: 0,
+            recvs_blocked: 0,
+        }
+    );
+
+    // the producer fills the channel and blocks on the third value
+    let mut producer = Fiber::new("test_fiber", &mut |channel: Box<Channel<i32>>| {
+        for i in 0..5 {
+            channel.send(i).unwrap();
+        }
+        0
+    });
+    producer.set_joinable(true);
+    producer.start(channel.clone());
+
+    let metrics = channel.metrics();
+    assert_eq!(metrics.len, 2);
+    assert_eq!(metrics.sends_blocked, 1);
+    assert_eq!(metrics.recvs_blocked, 0);
+
+    for i in 0..5 {
+        assert_eq!(channel.recv(), Ok(i));
+    }
+    producer.join().unwrap();
+    assert!(channel.metrics().sends_blocked >= 2);
+
+    let recvs_blocked = channel.metrics().recvs_blocked;
+    assert_eq!(
+        channel.recv_timeout(Duration::from_millis(1)),
+        Err(RecvTimeoutError::Timeout)
+    );
+    assert_eq!(channel.metrics().recvs_blocked, recvs_blocked + 1);
+    assert_eq!(channel.metrics().len, 0);
+}
@@ -1,10 +1,14 @@
+use std::cell::Cell;
 use std::convert::TryInto;
-use std::io::{Read, Write};
+use std::io::{self, IoSlice, IoSliceMut, Read, Write};
 use std::net::{TcpListener, TcpStream};
 use std::os::unix::io::{AsRawFd, FromRawFd};
 use std::os::unix::net::UnixStream;
+use std::time::Duration;
 
-use tarantool::coio::{coio_call, CoIOListener, CoIOStream};
+use tarantool::clock::{monotonic, Deadline};
+use tarantool::coio::{self, coio_call, CoIOListener, CoIOStream};
+use tarantool::error::Error;
 use tarantool::fiber::{sleep, Fiber};
 
 pub fn test_coio_accept() {
@@ -45,8 +49,8 @@ pub fn test_coio_read_write() {
     writer_fiber.set_joinable(true);
     writer_fiber.start(writer_soc);
 
-    reader_fiber.join();
-    writer_fiber.join();
+    reader_fiber.join().unwrap();
+    writer_fiber.join().unwrap();
 }
 
 pub fn test_coio_call() {
@@ -59,3 +63,157 @@ pub fn test_coio_call() {
     );
     assert_eq!(res, 100)
 }
+
+pub fn test_coio_call_closure() {
+    let ticks = Cell::new(0);
+    let mut ticker = Fiber::new("test_fiber", &mut |_| {
+        for _ in 0..10 {
+            ticks.set(ticks.get() + 1);
+            sleep(0.01);
+        }
+        0
+    });
+    ticker.set_joinable(true);
+    ticker.start(());
+
+    let input = [1, 2, 3];
+    let res = coio::call(move || {
+        std::thread::sleep(Duration::from_millis(200));
+        input.iter().sum::<i32>()
+    })
+    .unwrap();
+    assert_eq!(res, 6);
+    assert!(ticks.get() > 1);
+
+    ticker.join().unwrap();
+}
+
+pub fn test_coio_read_errno() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = CoIOStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+
+    let mut buf = [0; 4];
+    let err: Error = client
+        .read_with_timeout(&mut buf, Some(Duration::from_millis(10)))
+        .unwrap_err()
+        .into();
+    assert_eq!(err.io_kind(), Some(io::ErrorKind::TimedOut));
+    assert!(err.errno().is_some());
+
+    // closing a socket with unread data makes the peer receive RST
+    client.write_all(b"unread").unwrap();
+    sleep(0.01);
+    drop(server);
+
+    let err: Error = client.read(&mut buf).unwrap_err().into();
+    assert_eq!(err.io_kind(), Some(io::ErrorKind::ConnectionReset));
+    match err {
+        Error::Io {
+            errno,
+            kind: io::ErrorKind::ConnectionReset,
+        } => assert_eq!(
+            io::Error::from_raw_os_error(errno).kind(),
+            io::ErrorKind::ConnectionReset
+        ),
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+pub fn test_coio_vectored_io() {
+    let (reader_soc, writer_soc) = UnixStream::pair().unwrap();
+    let mut reader = CoIOStream::new(reader_soc).unwrap();
+    let mut writer = CoIOStream::new(writer_soc).unwrap();
+
+    let header = [0xaa, 0xbb, 0xcc, 0xdd];
+    let body = vec![7u8; 1 << 20];
+
+    let mut writer_fiber = Fiber::new("test_fiber", &mut |_| {
+        // the body doesn't fit into the socket buffer, so partial writes happen
+        writer
+            .write_all_vectored(&[IoSlice::new(&header), IoSlice::new(&body)])
+            .unwrap();
+        0
+    });
+    writer_fiber.set_joinable(true);
+    writer_fiber.start(());
+
+    let mut header_buf = [0u8; 4];
+    let mut body_buf = vec![0u8; body.len()];
+    let mut received = 0;
+    while received < header_buf.len() + body_buf.len() {
+        let (header_rest, body_rest) = if received < header_buf.len() {
+            (&mut header_buf[received..], &mut body_buf[..])
+        } else {
+            (
+                &mut header_buf[..0],
+                &mut body_buf[received - header.len()..],
+            )
+        };
+        let len = reader
+            .read_vectored(&mut [IoSliceMut::new(header_rest), IoSliceMut::new(body_rest)])
+            .unwrap();
+        assert!(len > 0);
+        received += len;
+    }
+    writer_fiber.join().unwrap();
+
+    assert_eq!(header_buf, header);
+    assert!(body_buf == body);
+}
+
+pub fn test_coio_read_until_deadline() {
+    let (reader_soc, writer_soc) = UnixStream::pair().unwrap();
+    let mut reader = CoIOStream::new(reader_soc).unwrap();
+    let mut writer = CoIOStream::new(writer_soc).unwrap();
+
+    // the message is sent in 3 packets with 50 ms pauses
+    let mut writer_fiber = Fiber::new("test_fiber", &mut |_| {
+        for packet in [[1u8; 4], [2; 4], [3; 4]].iter() {
+            writer.write_all(packet).unwrap();
+            sleep(0.05);
+        }
+        0
+    });
+    writer_fiber.set_joinable(true);
+    writer_fiber.start(());
+
+    // every packet arrives within 80 ms, but the whole message doesn't
+    let start = monotonic();
+    let mut buf = [0u8; 12];
+    let err = reader
+        .read_until_deadline(&mut buf, Deadline::after(Duration::from_millis(80)))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    assert!(monotonic() - start < 0.1);
+    writer_fiber.join().unwrap();
+
+    let mut writer_fiber = Fiber::new("test_fiber", &mut |_| {
+        for packet in [[4u8; 4], [5; 4]].iter() {
+            writer.write_all(packet).unwrap();
+            sleep(0.01);
+        }
+        0
+    });
+    writer_fiber.set_joinable(true);
+    writer_fiber.start(());
+    let mut buf = [0u8; 8];
+    reader
+        .read_until_deadline(&mut buf, Deadline::after(Duration::from_secs(1)))
+        .unwrap();
+    assert_eq!(buf, [4, 4, 4, 4, 5, 5, 5, 5]);
+    writer_fiber.join().unwrap();
+}
+
+pub fn test_coio_connect_deadline() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let expired = Deadline::after(Duration::from_secs(0));
+    let err = CoIOStream::connect_deadline(&addr, expired)
+        .map(drop)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    assert!(CoIOStream::connect_deadline(&addr, Deadline::after(Duration::from_secs(1))).is_ok());
+}
@@ -1,5 +1,8 @@
-use tarantool::error::TarantoolError;
+use std::io;
+
+use tarantool::error::{Error, Result, TarantoolError, TarantoolErrorCode};
 use tarantool::space::Space;
+use tarantool::{bail, ensure};
 
 use crate::common::S1Record;
 
@@ -16,3 +19,51 @@ pub fn test_error_last() {
     assert!(result.is_err());
     assert!(TarantoolError::maybe_last().is_err());
 }
+
+pub fn test_error_as_tarantool() {
+    let mut space = Space::find("test_s1").unwrap();
+    let input = S1Record {
+        id: 0,
+        text: "".to_string(),
+    };
+    space.truncate().unwrap();
+    space.insert(&input).unwrap();
+
+    let err = space.insert(&input).map(drop).unwrap_err();
+    let box_error = err.as_tarantool().unwrap();
+    assert_eq!(box_error.error_code(), TarantoolErrorCode::TupleFound);
+    assert_eq!(box_error.error_type(), "ClientError");
+
+    let err: Error = io::Error::from(io::ErrorKind::TimedOut).into();
+    assert!(err.as_tarantool().is_none());
+}
+
+pub fn test_error_ensure() {
+    fn double(value: i32) -> Result<i32> {
+        ensure!(
+            value >= 0,
+            TarantoolErrorCode::IllegalParams,
+            "Negative value: {}",
+            value
+        );
+        if value > 1000 {
+            bail!(TarantoolErrorCode::ProcLua, "Value {} is too big", value);
+        }
+        Ok(value * 2)
+    }
+
+    assert_eq!(double(2).unwrap(), 4);
+
+    match double(-1) {
+        Err(Error::Tarantool(e)) => {
+            assert_eq!(e.error_code(), TarantoolErrorCode::IllegalParams);
+            assert_eq!(e.to_string(), "IllegalParams: Negative value: -1");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    match double(1001) {
+        Err(Error::Tarantool(e)) => assert_eq!(e.error_code(), TarantoolErrorCode::ProcLua),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
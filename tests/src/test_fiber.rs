@@ -1,13 +1,19 @@
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::time::Duration;
 
-use tarantool::fiber::{fiber_yield, is_cancelled, sleep, Cond, Fiber, FiberAttr};
+use tarantool::error::{Error, TarantoolErrorCode};
+use tarantool::fiber::channel::Channel;
+use tarantool::fiber::{
+    context, default_stack_size, fiber_yield, is_cancelled, reschedule, set_context, sleep,
+    spawn_with_context, yields, Builder, Cond, Fiber, FiberAttr,
+};
 
 pub fn test_fiber_new() {
     let mut fiber = Fiber::new("test_fiber", &mut |_| 0);
     fiber.set_joinable(true);
     fiber.start(());
-    fiber.join();
+    fiber.join().unwrap();
 }
 
 pub fn test_fiber_new_with_attr() {
@@ -17,7 +23,100 @@ pub fn test_fiber_new_with_attr() {
     let mut fiber = Fiber::new_with_attr("test_fiber", &attr, &mut |_| 0);
     fiber.set_joinable(true);
     fiber.start(());
-    fiber.join();
+    fiber.join().unwrap();
+}
+
+pub fn test_fiber_builder() {
+    let mut fiber = Builder::new()
+        .name(format!("test_fiber_{}", 1))
+        .stack_size(100_000)
+        .unwrap()
+        .build(&mut |_| 0);
+    assert_eq!(fiber.name(), "test_fiber_1");
+    fiber.set_joinable(true);
+    fiber.start(());
+    fiber.join().unwrap();
+
+    let mut fiber = Builder::new().build(&mut |_| 0);
+    assert_eq!(fiber.name(), Builder::DEFAULT_NAME);
+    fiber.start(());
+}
+
+pub fn test_fiber_stack_size() {
+    // tarantool allocates fiber stacks no smaller than 16 KiB
+    let default = default_stack_size();
+    assert!(default >= 16 * 1024);
+    assert!(default <= 1 << 30);
+    assert_eq!(Builder::new().get_stack_size(), default);
+
+    let builder = Builder::new().stack_size(200_000).unwrap();
+    assert_eq!(builder.get_stack_size(), 200_000);
+    assert_eq!(default_stack_size(), default);
+}
+
+pub fn test_fiber_yields() {
+    let before = yields();
+    for _ in 0..3 {
+        reschedule();
+    }
+    let after = yields();
+    assert!(after >= before + 3);
+
+    // a loop which never yields
+    let mut sum = 0u64;
+    for i in 0..100_000u64 {
+        sum = sum.wrapping_add(i * i);
+    }
+    assert!(sum > 0);
+    assert_eq!(yields(), after);
+
+    // a fiber which sleeps yields
+    let mut fiber = Fiber::new("test_fiber", &mut |_| {
+        let start = yields();
+        sleep(0.001);
+        assert!(yields() > start);
+        0
+    });
+    fiber.set_joinable(true);
+    fiber.start(());
+    fiber.join().unwrap();
+}
+
+pub fn test_fiber_spawn_with_context() {
+    #[derive(Debug, PartialEq)]
+    struct TraceId(u64);
+
+    assert!(context::<TraceId>().is_none());
+    let guard = set_context(TraceId(42));
+    assert_eq!(*context::<TraceId>().unwrap(), TraceId(42));
+    assert!(context::<String>().is_none());
+
+    let channel = Channel::new(1);
+    let tx = channel.clone();
+    spawn_with_context(context::<TraceId>().unwrap(), move || {
+        sleep(0.001);
+        tx.send(context::<TraceId>().map(|trace_id| trace_id.0))
+            .unwrap();
+    });
+    assert_eq!(channel.recv(), Ok(Some(42)));
+
+    // fibers started without a context don't inherit one
+    let mut fiber = Fiber::new("test_fiber", &mut |_| {
+        assert!(context::<TraceId>().is_none());
+        0
+    });
+    fiber.set_joinable(true);
+    fiber.start(());
+    fiber.join().unwrap();
+
+    // nested contexts are restored in order
+    {
+        let _inner = set_context(TraceId(7));
+        assert_eq!(*context::<TraceId>().unwrap(), TraceId(7));
+    }
+    assert_eq!(*context::<TraceId>().unwrap(), TraceId(42));
+    drop(guard);
+    assert!(context::<TraceId>().is_none());
 }
 
 pub fn test_fiber_arg() {
@@ -27,7 +126,25 @@ pub fn test_fiber_arg() {
     });
     fiber.set_joinable(true);
     fiber.start(99);
-    fiber.join();
+    fiber.join().unwrap();
+}
+
+pub fn test_fiber_panic() {
+    let mut fiber = Fiber::new("test_fiber", &mut |_| -> i32 {
+        panic!("something went wrong");
+    });
+    fiber.set_joinable(true);
+    fiber.start(());
+
+    match fiber.join() {
+        Err(Error::Tarantool(e)) => {
+            assert_eq!(e.error_code(), TarantoolErrorCode::ProcC);
+            assert!(e
+                .to_string()
+                .contains("fiber panicked: something went wrong"));
+        }
+        result => panic!("Unexpected join result: {:?}", result),
+    }
 }
 
 pub fn test_fiber_cancel() {
@@ -40,7 +157,7 @@ pub fn test_fiber_cancel() {
     fiber.set_joinable(true);
     fiber.start(());
     fiber.cancel();
-    fiber.join();
+    fiber.join().unwrap();
 }
 
 pub fn test_fiber_wake() {
@@ -52,7 +169,7 @@ pub fn test_fiber_wake() {
     fiber.start(());
     sleep(0.01);
     fiber.wakeup();
-    fiber.join();
+    fiber.join().unwrap();
 }
 
 pub fn test_fiber_cond_signal() {
@@ -65,7 +182,7 @@ pub fn test_fiber_cond_signal() {
     fiber.start(cond.clone());
     sleep(0.01);
     cond.signal();
-    fiber.join();
+    fiber.join().unwrap();
 }
 
 pub fn test_fiber_cond_broadcast() {
@@ -87,8 +204,74 @@ pub fn test_fiber_cond_broadcast() {
 
     sleep(0.01);
     cond.broadcast();
-    fiber_a.join();
-    fiber_b.join();
+    fiber_a.join().unwrap();
+    fiber_b.join().unwrap();
+}
+
+pub fn test_fiber_cond_signal_fifo() {
+    let cond = Cond::new();
+    let woken = RefCell::new(vec![]);
+
+    let mut waiter = |i: Box<i32>| {
+        cond.wait();
+        woken.borrow_mut().push(*i);
+        0
+    };
+    let mut fibers = vec![];
+    for i in 0..3 {
+        let mut fiber = Fiber::new("test_fiber", &mut waiter);
+        fiber.set_joinable(true);
+        fiber.start(i);
+        fibers.push(fiber);
+    }
+
+    for expected in 1..=3 {
+        cond.signal();
+        sleep(0.01);
+        assert_eq!(woken.borrow().len(), expected);
+    }
+    assert_eq!(*woken.borrow(), vec![0, 1, 2]);
+
+    for fiber in fibers {
+        fiber.join().unwrap();
+    }
+}
+
+pub fn test_fiber_cond_broadcast_all() {
+    let cond = Cond::new();
+    let woken = RefCell::new(vec![]);
+
+    let mut waiter = |i: Box<i32>| {
+        cond.wait();
+        woken.borrow_mut().push(*i);
+        0
+    };
+    let mut fibers = vec![];
+    for i in 0..3 {
+        let mut fiber = Fiber::new("test_fiber", &mut waiter);
+        fiber.set_joinable(true);
+        fiber.start(i);
+        fibers.push(fiber);
+    }
+
+    cond.broadcast();
+    // a broadcast doesn't affect fibers which start waiting after it
+    let late_woken = Cell::new(true);
+    let mut late = Fiber::new("test_fiber", &mut |_| {
+        late_woken.set(cond.wait_timeout(Duration::from_millis(50)));
+        0
+    });
+    late.set_joinable(true);
+    late.start(());
+
+    sleep(0.01);
+    assert_eq!(*woken.borrow(), vec![0, 1, 2]);
+
+    for fiber in fibers {
+        fiber.join().unwrap();
+    }
+    late.join().unwrap();
+    assert!(!late_woken.get());
 }
 
 pub fn test_fiber_cond_timeout() {
@@ -102,5 +285,5 @@ pub fn test_fiber_cond_timeout() {
     fiber.start(cond.clone());
     sleep(0.02);
     cond.signal();
-    fiber.join();
+    fiber.join().unwrap();
 }
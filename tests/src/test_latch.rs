@@ -14,7 +14,7 @@ pub fn test_latch_lock() {
     fiber.set_joinable(true);
     fiber.start(());
     latch.lock();
-    fiber.join();
+    fiber.join().unwrap();
 }
 
 pub fn test_latch_try_lock() {
@@ -30,6 +30,6 @@ pub fn test_latch_try_lock() {
     fiber.start(());
     assert!(latch.try_lock().is_none());
 
-    fiber.join();
+    fiber.join().unwrap();
     assert!(latch.try_lock().is_some());
 }
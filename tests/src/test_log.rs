@@ -1,5 +1,10 @@
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+
 use log::{warn, LevelFilter};
 
+use tarantool::ffi::tarantool as ffi;
 use tarantool::log::{say, SayLevel, TarantoolLogger};
 
 pub fn test_log() {
@@ -9,3 +14,44 @@ pub fn test_log() {
 
     say(SayLevel::Warn, "<file>", 0, Some("<error>"), "<message>");
 }
+
+type SinkFn = unsafe extern "C" fn(c_int, *const c_char, c_int, *const c_char, *const c_char);
+
+thread_local! {
+    static SAY_CALL: RefCell<Option<(String, i32, String)>> = const { RefCell::new(None) };
+}
+
+unsafe extern "C" fn capture_say(
+    _level: c_int,
+    file: *const c_char,
+    line: c_int,
+    _error: *const c_char,
+    message: *const c_char,
+) {
+    let call = (
+        CStr::from_ptr(file).to_string_lossy().into_owned(),
+        line,
+        CStr::from_ptr(message).to_string_lossy().into_owned(),
+    );
+    SAY_CALL.with(|c| *c.borrow_mut() = Some(call));
+}
+
+pub fn test_log_file_line() {
+    // the logger may be already set by another test
+    let _ = log::set_logger(&TarantoolLogger {});
+    log::set_max_level(LevelFilter::Debug);
+
+    // replace `say` with a sink capturing the call (the sink ignores variadic arguments)
+    let original_say = unsafe { ffi::SAY_FN };
+    unsafe {
+        ffi::SAY_FN = std::mem::transmute::<Option<SinkFn>, ffi::SayFunc>(Some(capture_say));
+    }
+    warn!("located message");
+    let expected_line = line!() - 1;
+    unsafe { ffi::SAY_FN = original_say };
+
+    let (file, line, message) = SAY_CALL.with(|c| c.borrow_mut().take()).unwrap();
+    assert!(file.ends_with("test_log.rs"), "unexpected file: {}", file);
+    assert_eq!(line, expected_line as i32);
+    assert_eq!(message, "located message");
+}
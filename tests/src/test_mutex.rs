@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use tarantool::fiber::{sleep, CondVar, Fiber, Mutex};
+
+pub fn test_mutex_lock() {
+    let mutex = Mutex::new(0);
+
+    let mut fiber = Fiber::new("test_fiber", &mut |_| {
+        let mut guard = mutex.lock();
+        sleep(0.01);
+        *guard += 1;
+        0
+    });
+    fiber.set_joinable(true);
+    fiber.start(());
+
+    assert!(mutex.try_lock().is_none());
+    *mutex.lock() += 1;
+    fiber.join().unwrap();
+    assert_eq!(mutex.into_inner(), 2);
+}
+
+pub fn test_condvar_bounded_queue() {
+    const CAPACITY: usize = 2;
+    let queue = CondVar::new(VecDeque::new());
+
+    let queue = &queue;
+    let producer = || {
+        move |base: Box<i32>| {
+            for i in 0..10 {
+                let mut guard = queue.wait_while(|q| q.len() >= CAPACITY);
+                guard.push_back(*base + i);
+                assert!(guard.len() <= CAPACITY);
+                drop(guard);
+                queue.notify_all();
+            }
+            0
+        }
+    };
+
+    let mut producers = vec![];
+    let mut callbacks = [producer(), producer()];
+    for (callback, base) in callbacks.iter_mut().zip([0, 100].iter()) {
+        let mut fiber = Fiber::new("test_fiber", callback);
+        fiber.set_joinable(true);
+        fiber.start(*base);
+        producers.push(fiber);
+    }
+
+    let mut received = vec![];
+    while received.len() < 20 {
+        let mut guard = queue.wait_while(|q| q.is_empty());
+        received.push(guard.pop_front().unwrap());
+        drop(guard);
+        queue.notify_all();
+    }
+
+    for fiber in producers {
+        fiber.join().unwrap();
+    }
+
+    received.sort_unstable();
+    let expected: Vec<i32> = (0..10).chain(100..110).collect();
+    assert_eq!(received, expected);
+}
+
+pub fn test_condvar_wait_timeout() {
+    let flag = CondVar::new(false);
+
+    let (guard, timed_out) = flag.wait_timeout_while(Duration::from_millis(10), |flag| !*flag);
+    assert!(timed_out);
+    assert!(!*guard);
+    drop(guard);
+
+    let mut fiber = Fiber::new("test_fiber", &mut |_| {
+        *flag.lock() = true;
+        flag.notify_one();
+        0
+    });
+    fiber.set_joinable(true);
+    fiber.start(());
+
+    let (guard, timed_out) = flag.wait_timeout_while(Duration::from_secs(1), |flag| !*flag);
+    assert!(!timed_out);
+    assert!(*guard);
+    drop(guard);
+    fiber.join().unwrap();
+}
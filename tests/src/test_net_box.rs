@@ -1,12 +1,19 @@
-use std::io;
+use std::convert::TryInto;
+use std::io::{self, Read, Write};
+use std::net::TcpListener;
 use std::rc::Rc;
 use std::time::Duration;
 
+use tarantool::clock::{monotonic, Deadline};
+use tarantool::coio::{CoIOListener, CoIOStream};
 use tarantool::error::Error;
-use tarantool::fiber::Fiber;
+use tarantool::fiber::{self, Fiber};
 use tarantool::index::IteratorType;
-use tarantool::net_box::{Conn, ConnOptions, ConnTriggers, Options};
+use tarantool::net_box::{
+    Conn, ConnFeatures, ConnOptions, ConnTriggers, Options, RequestEvent, RequestKind,
+};
 use tarantool::space::Space;
+use tarantool::tuple::AsTuple;
 
 use crate::common::{QueryOperation, S1Record, S2Record};
 use std::cell::{Cell, RefCell};
@@ -54,8 +61,8 @@ pub fn test_ping_concurrent() {
     fiber_a.start(conn.clone());
     fiber_b.start(conn.clone());
 
-    fiber_a.join();
-    fiber_b.join();
+    fiber_a.join().unwrap();
+    fiber_b.join().unwrap();
 }
 
 pub fn test_call() {
@@ -71,6 +78,26 @@ pub fn test_call() {
     assert_eq!(result.unwrap().into_struct::<(i32,)>().unwrap(), (3,));
 }
 
+pub fn test_call_raw() {
+    let conn_options = ConnOptions {
+        user: "test_user".to_string(),
+        password: "password".to_string(),
+        ..ConnOptions::default()
+    };
+    let conn = Conn::new("localhost:3301", conn_options, None).unwrap();
+    let options = Options::default();
+
+    let raw = conn
+        .call_raw("test_stored_proc", &(1, 2), &options)
+        .unwrap();
+    let typed = conn
+        .call("test_stored_proc", &(1, 2), &options)
+        .unwrap()
+        .unwrap();
+    assert_eq!(raw, typed.as_raw_bytes());
+    assert_eq!(raw, vec![0x91, 0x03]);
+}
+
 pub fn test_call_timeout() {
     let conn_options = ConnOptions {
         user: "test_user".to_string(),
@@ -89,6 +116,125 @@ pub fn test_call_timeout() {
     assert!(matches!(result, Err(Error::IO(ref e)) if e.kind() == io::ErrorKind::TimedOut));
 }
 
+pub fn test_request_timeout() {
+    let conn_options = ConnOptions {
+        user: "test_user".to_string(),
+        password: "password".to_string(),
+        connect_timeout: Duration::from_secs(10),
+        request_timeout: Some(Duration::from_millis(10)),
+        ..ConnOptions::default()
+    };
+    let conn = Conn::new("localhost:3301", conn_options, None).unwrap();
+
+    // the connection is established in time, but the slow call is not
+    let result = conn.call("test_timeout", &Vec::<()>::new(), &Options::default());
+    assert!(matches!(result, Err(Error::IO(ref e)) if e.kind() == io::ErrorKind::TimedOut));
+
+    // the connection remains usable
+    assert!(conn.is_connected());
+    let result = conn.call("test_stored_proc", &(1, 2), &Options::default());
+    assert_eq!(
+        result.unwrap().unwrap().into_struct::<(i32,)>().unwrap(),
+        (3,)
+    );
+
+    // explicit request timeout takes precedence
+    conn.ping(&Options {
+        timeout: Some(Duration::from_secs(1)),
+        ..Options::default()
+    })
+    .unwrap();
+}
+
+pub fn test_call_with_deadline() {
+    let conn_options = ConnOptions {
+        user: "test_user".to_string(),
+        password: "password".to_string(),
+        ..ConnOptions::default()
+    };
+    let conn = Conn::new("localhost:3301", conn_options, None).unwrap();
+    conn.ping(&Options::default()).unwrap();
+
+    let deadline = Deadline::after(Duration::from_millis(100));
+    for i in 0..2 {
+        let result = conn
+            .call_with_deadline("test_stored_proc", &(i, 1), deadline)
+            .unwrap();
+        assert_eq!(result.unwrap().into_struct::<(i32,)>().unwrap(), (i + 1,));
+    }
+
+    fiber::sleep(deadline.remaining().as_secs_f64() + 0.01);
+    let start = monotonic();
+    let result = conn.call_with_deadline("test_stored_proc", &(2, 1), deadline);
+    assert!(matches!(result, Err(Error::IO(ref e)) if e.kind() == io::ErrorKind::TimedOut));
+    // rejected without waiting for the server
+    assert!(monotonic() - start < 0.01);
+}
+
+pub fn test_tracer() {
+    let conn_options = ConnOptions {
+        user: "test_user".to_string(),
+        password: "password".to_string(),
+        ..ConnOptions::default()
+    };
+    let conn = Conn::new("localhost:3301", conn_options, None).unwrap();
+    let events = Rc::new(RefCell::new(Vec::new()));
+    {
+        let events = events.clone();
+        conn.set_tracer(move |event| events.borrow_mut().push(event));
+    }
+
+    conn.eval(
+        "require('fiber').sleep(0.01) return ...",
+        &(1,),
+        &Options::default(),
+    )
+    .unwrap();
+    let result = conn.call("no_such_function", &(1,), &Options::default());
+    assert!(result.is_err());
+
+    conn.remove_tracer();
+    conn.ping(&Options::default()).unwrap();
+
+    let events = events.borrow();
+    assert_eq!(events.len(), 4);
+    let sync = match events[0] {
+        RequestEvent::Start { kind, sync } => {
+            assert_eq!(kind, RequestKind::Eval);
+            sync
+        }
+        ref event => panic!("unexpected event: {:?}", event),
+    };
+    match events[1] {
+        RequestEvent::Finish {
+            kind,
+            sync: finish_sync,
+            elapsed,
+        } => {
+            assert_eq!(kind, RequestKind::Eval);
+            assert_eq!(finish_sync, sync);
+            assert!(elapsed >= Duration::from_millis(10));
+        }
+        ref event => panic!("unexpected event: {:?}", event),
+    }
+    assert!(matches!(
+        events[2],
+        RequestEvent::Start {
+            kind: RequestKind::Call,
+            ..
+        }
+    ));
+    match events[3] {
+        RequestEvent::Error {
+            kind, ref error, ..
+        } => {
+            assert_eq!(kind, RequestKind::Call);
+            assert!(error.contains("no_such_function"), "{}", error);
+        }
+        ref event => panic!("unexpected event: {:?}", event),
+    }
+}
+
 pub fn test_eval() {
     let conn_options = ConnOptions {
         user: "test_user".to_string(),
@@ -102,6 +248,122 @@ pub fn test_eval() {
     assert_eq!(result.unwrap().into_struct::<(i32, i32)>().unwrap(), (1, 2));
 }
 
+pub fn test_eval_named() {
+    let conn_options = ConnOptions {
+        user: "test_user".to_string(),
+        password: "password".to_string(),
+        ..ConnOptions::default()
+    };
+    let conn = Conn::new("localhost:3301", conn_options, None).unwrap();
+    let options = Options::default();
+
+    let result = conn
+        .eval_named("test_chunk", "return ...", &(1, 2), &options)
+        .unwrap();
+    assert_eq!(result.unwrap().into_struct::<(i32, i32)>().unwrap(), (1, 2));
+
+    let result = conn
+        .eval_named("test_chunk", "return 42", &(), &options)
+        .unwrap();
+    assert_eq!(result.unwrap().into_struct::<(i32,)>().unwrap(), (42,));
+
+    let err = conn
+        .eval_named("test_chunk", "local a = 1\nerror('boom')", &(), &options)
+        .map(drop)
+        .unwrap_err();
+    assert!(err.to_string().contains("test_chunk:2: boom"), "{}", err);
+
+    let err = conn
+        .eval_named("test_chunk", "return )", &(), &options)
+        .map(drop)
+        .unwrap_err();
+    assert!(err.to_string().contains("test_chunk:1:"), "{}", err);
+}
+
+pub fn test_register_function() {
+    let conn_options = ConnOptions {
+        user: "test_user".to_string(),
+        password: "password".to_string(),
+        ..ConnOptions::default()
+    };
+    let conn = Conn::new("localhost:3301", conn_options, None).unwrap();
+    let options = Options::default();
+
+    conn.register_function(
+        "test_registered_fn",
+        "function(a, b) return a * b end",
+        &options,
+    )
+    .unwrap();
+    let result = conn.call("test_registered_fn", &(6, 7), &options).unwrap();
+    assert_eq!(result.unwrap().into_struct::<(i32,)>().unwrap(), (42,));
+
+    conn.eval(
+        "box.schema.user.grant('guest', 'execute', 'function', ...)",
+        &("test_registered_fn",),
+        &options,
+    )
+    .unwrap();
+
+    // re-registration replaces the function and keeps the grants
+    conn.register_function(
+        "test_registered_fn",
+        "function(a, b) return a - b end",
+        &options,
+    )
+    .unwrap();
+    let result = conn.call("test_registered_fn", &(6, 7), &options).unwrap();
+    assert_eq!(result.unwrap().into_struct::<(i32,)>().unwrap(), (-1,));
+    let guest_conn = Conn::new("localhost:3301", ConnOptions::default(), None).unwrap();
+    let result = guest_conn
+        .call("test_registered_fn", &(6, 7), &options)
+        .unwrap();
+    assert_eq!(result.unwrap().into_struct::<(i32,)>().unwrap(), (-1,));
+
+    // a failed re-registration leaves the old function in place
+    assert!(conn
+        .register_function("test_registered_fn", "function(", &options)
+        .is_err());
+    let result = conn.call("test_registered_fn", &(6, 7), &options).unwrap();
+    assert_eq!(result.unwrap().into_struct::<(i32,)>().unwrap(), (-1,));
+
+    conn.drop_function("test_registered_fn", &options).unwrap();
+    assert!(matches!(
+        conn.call("test_registered_fn", &(6, 7), &options),
+        Err(Error::Remote(_))
+    ));
+
+    // dropping a missing function is not an error
+    conn.drop_function("test_registered_fn", &options).unwrap();
+}
+
+pub fn test_remote_error_chain() {
+    let conn_options = ConnOptions {
+        user: "test_user".to_string(),
+        password: "password".to_string(),
+        ..ConnOptions::default()
+    };
+    let conn = Conn::new("localhost:3301", conn_options, None).unwrap();
+    let error = match conn.call("test_chained_error", &Vec::<()>::new(), &Options::default()) {
+        Err(Error::Remote(error)) => error,
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    };
+    assert_eq!(error.code(), 1002);
+    assert_eq!(error.message(), "outer failure");
+
+    let outer = error.error().unwrap();
+    assert_eq!(outer.code, 1002);
+    assert_eq!(outer.message, "outer failure");
+    assert_eq!(outer.error_type, "CustomError");
+    assert_eq!(outer.fields["custom_type"], "OuterError");
+
+    let inner = outer.prev.as_ref().unwrap();
+    assert_eq!(inner.code, 1001);
+    assert_eq!(inner.message, "inner failure");
+    assert_eq!(inner.fields["custom_type"], "InnerError");
+    assert!(inner.prev.is_none());
+}
+
 pub fn test_connection_error() {
     let conn = Conn::new(
         "localhost:255",
@@ -130,6 +392,215 @@ pub fn test_is_connected() {
     assert_eq!(conn.is_connected(), true);
 }
 
+pub fn test_peer_version() {
+    let conn = Conn::new(
+        "localhost:3301",
+        ConnOptions {
+            user: "test_user".to_string(),
+            password: "password".to_string(),
+            ..ConnOptions::default()
+        },
+        None,
+    )
+    .unwrap();
+
+    let (version,) = conn
+        .eval(
+            "return box.info.version",
+            &Vec::<()>::new(),
+            &Options::default(),
+        )
+        .unwrap()
+        .unwrap()
+        .into_struct::<(String,)>()
+        .unwrap();
+    let mut expected = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse::<u32>().unwrap());
+    let expected = (
+        expected.next().unwrap(),
+        expected.next().unwrap(),
+        expected.next().unwrap(),
+    );
+    assert_eq!(conn.peer_version().unwrap(), expected);
+
+    let features = conn.features().unwrap();
+    if expected >= (2, 10, 0) {
+        assert!(features.protocol_version >= 3);
+        assert!(features.streams);
+        assert!(features.transactions);
+        assert!(features.watchers);
+    }
+}
+
+/// Accepts a connection on the mock server and performs the handshake of Tarantool older than 2.10: sends the
+/// greeting and rejects `IPROTO_ID`.
+fn mock_accept(listener: &CoIOListener) -> CoIOStream {
+    let mut stream = listener.accept().unwrap();
+    let mut greeting = format!(
+        "{:63}\n",
+        "Tarantool 1.10.0 (Binary) 00000000-0000-0000-0000-000000000000"
+    );
+    greeting.push_str(&format!("{:63}\n", "A".repeat(44)));
+    stream.write_all(greeting.as_bytes()).unwrap();
+
+    // IPROTO_ID: reject with ER_UNKNOWN_REQUEST_TYPE
+    let (request_type, sync) = mock_read_request(&mut stream).unwrap();
+    assert_eq!(request_type, 73);
+    let mut error = vec![0x81, 0x31, 0xb7];
+    error.extend_from_slice(b"Unknown request type 73");
+    mock_write_response(&mut stream, &[0xcd, 0x80, 0x30], sync, &error).unwrap();
+    stream
+}
+
+/// Reads a request sent by `Conn` to the mock server, returns its type and sync (both expected to be small).
+///
+/// Returns `None` if the connection is closed.
+fn mock_read_request(stream: &mut CoIOStream) -> Option<(u8, u8)> {
+    let mut len = [0u8; 5];
+    stream.read_exact(&mut len).ok()?;
+    assert_eq!(len[0], 0xce);
+    let mut request = vec![0u8; u32::from_be_bytes([len[1], len[2], len[3], len[4]]) as usize];
+    stream.read_exact(&mut request).ok()?;
+    // header: {REQUEST_TYPE: type, SYNC: sync}
+    assert_eq!(request[0..2], [0x82, 0x00]);
+    assert!(request[4] < 0x80);
+    Some((request[2], request[4]))
+}
+
+/// Writes a response to the mock server's client. `status` must be encoded as msgpack uint.
+fn mock_write_response(
+    stream: &mut CoIOStream,
+    status: &[u8],
+    sync: u8,
+    body: &[u8],
+) -> io::Result<()> {
+    // header: {REQUEST_TYPE: status, SYNC: sync, SCHEMA_VERSION: 1}
+    let mut response = vec![0x83, 0x00];
+    response.extend_from_slice(status);
+    response.extend_from_slice(&[0x01, sync, 0x05, 0x01]);
+    response.extend_from_slice(body);
+
+    stream.write_all(&[0xce])?;
+    stream.write_all(&(response.len() as u32).to_be_bytes())?;
+    stream.write_all(&response)
+}
+
+pub fn test_features_fallback() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let listener: CoIOListener = listener.try_into().unwrap();
+
+    // mock server, which behaves like Tarantool older than 2.10
+    let mut server_fiber = Fiber::new("test_fiber", &mut |_| {
+        let mut stream = mock_accept(&listener);
+
+        // IPROTO_PING: ok
+        let (request_type, sync) = mock_read_request(&mut stream).unwrap();
+        assert_eq!(request_type, 64);
+        mock_write_response(&mut stream, &[0x00], sync, &[0x80]).unwrap();
+        0
+    });
+    server_fiber.set_joinable(true);
+    server_fiber.start(());
+
+    let conn = Conn::new(
+        addr,
+        ConnOptions {
+            reconnect_after: Duration::from_secs(0),
+            ..ConnOptions::default()
+        },
+        None,
+    )
+    .unwrap();
+    conn.ping(&Options::default()).unwrap();
+    assert_eq!(conn.peer_version().unwrap(), (1, 10, 0));
+    assert_eq!(conn.features().unwrap(), ConnFeatures::default());
+
+    server_fiber.join().unwrap();
+}
+
+pub fn test_features_id_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let listener: CoIOListener = listener.try_into().unwrap();
+
+    // mock server, which supports IPROTO_ID but fails it
+    let mut server_fiber = Fiber::new("test_fiber", &mut |_| {
+        let mut stream = listener.accept().unwrap();
+        let mut greeting = format!(
+            "{:63}\n",
+            "Tarantool 2.10.0 (Binary) 00000000-0000-0000-0000-000000000000"
+        );
+        greeting.push_str(&format!("{:63}\n", "A".repeat(44)));
+        stream.write_all(greeting.as_bytes()).unwrap();
+
+        // IPROTO_ID: reject with ER_ACCESS_DENIED
+        let (request_type, sync) = mock_read_request(&mut stream).unwrap();
+        assert_eq!(request_type, 73);
+        let mut error = vec![0x81, 0x31, 0xad];
+        error.extend_from_slice(b"Access denied");
+        mock_write_response(&mut stream, &[0xcd, 0x80, 0x2a], sync, &error).unwrap();
+        0
+    });
+    server_fiber.set_joinable(true);
+    server_fiber.start(());
+
+    let conn = Conn::new(
+        addr,
+        ConnOptions {
+            reconnect_after: Duration::from_secs(0),
+            ..ConnOptions::default()
+        },
+        None,
+    )
+    .unwrap();
+    assert!(conn.ping(&Options::default()).is_err());
+
+    server_fiber.join().unwrap();
+}
+
+pub fn test_keepalive() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let listener: CoIOListener = listener.try_into().unwrap();
+
+    // mock server answering pings until the connection is closed
+    let pings = Cell::new(0);
+    let mut server_fiber = Fiber::new("test_fiber", &mut |_| {
+        let mut stream = mock_accept(&listener);
+        while let Some((request_type, sync)) = mock_read_request(&mut stream) {
+            assert_eq!(request_type, 64);
+            pings.set(pings.get() + 1);
+            let _ = mock_write_response(&mut stream, &[0x00], sync, &[0x80]);
+        }
+        0
+    });
+    server_fiber.set_joinable(true);
+    server_fiber.start(());
+
+    let conn = Conn::new(
+        addr,
+        ConnOptions {
+            keepalive_interval: Some(Duration::from_millis(20)),
+            ..ConnOptions::default()
+        },
+        None,
+    )
+    .unwrap();
+    conn.ping(&Options::default()).unwrap();
+
+    // idle connection is pinged in background
+    let pings_before = pings.get();
+    fiber::sleep(0.1);
+    assert!(pings.get() - pings_before >= 3);
+    assert!(conn.is_connected());
+    conn.ping(&Options::default()).unwrap();
+
+    drop(conn);
+    server_fiber.join().unwrap();
+}
+
 pub fn test_schema_sync() {
     let conn = Conn::new(
         "localhost:3301",
@@ -221,6 +692,103 @@ pub fn test_select() {
     );
 }
 
+pub fn test_pipeline() {
+    let conn = Conn::new(
+        "localhost:3301",
+        ConnOptions {
+            user: "test_user".to_string(),
+            password: "password".to_string(),
+            ..ConnOptions::default()
+        },
+        None,
+    )
+    .unwrap();
+    let space = conn.space("test_s2").unwrap().unwrap();
+    let index = space.primary_key();
+
+    let keys = [(3,), (7,), (11,)];
+    let mut pipeline = conn.pipeline();
+    for key in keys.iter() {
+        pipeline.select(&index, IteratorType::Eq, key);
+    }
+    assert_eq!(pipeline.len(), 3);
+    let results = pipeline.send(&Options::default()).wait_all();
+    let ids: Vec<u32> = results
+        .into_iter()
+        .map(|result| {
+            let rows: Vec<S2Record> = result.unwrap().unwrap().into_struct().unwrap();
+            assert_eq!(rows.len(), 1);
+            rows[0].id
+        })
+        .collect();
+    assert_eq!(ids, vec![3, 7, 11]);
+
+    // requests are processed concurrently
+    let expr = "require('fiber').sleep(0.1) return ...";
+    let args = [(1,), (2,), (3,)];
+    let start = fiber::clock();
+    for arg in args.iter() {
+        conn.eval(expr, arg, &Options::default()).unwrap();
+    }
+    let sequential_time = fiber::clock() - start;
+
+    let mut pipeline = conn.pipeline();
+    for arg in args.iter() {
+        pipeline.eval(expr, arg);
+    }
+    let start = fiber::clock();
+    let results = pipeline.send(&Options::default()).wait_all();
+    let pipeline_time = fiber::clock() - start;
+
+    let results: Vec<(u32,)> = results
+        .into_iter()
+        .map(|result| result.unwrap().unwrap().into_struct().unwrap())
+        .collect();
+    assert_eq!(results, vec![(1,), (2,), (3,)]);
+    assert!(pipeline_time < sequential_time / 2.0);
+}
+
+/// Arguments which fail to encode by panicking.
+struct UnencodableArgs;
+
+impl serde::Serialize for UnencodableArgs {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("can't encode"))
+    }
+}
+
+impl AsTuple for UnencodableArgs {}
+
+pub fn test_pipeline_encode_error() {
+    let conn = Conn::new(
+        "localhost:3301",
+        ConnOptions {
+            user: "test_user".to_string(),
+            password: "password".to_string(),
+            ..ConnOptions::default()
+        },
+        None,
+    )
+    .unwrap();
+
+    let mut pipeline = conn.pipeline();
+    pipeline.eval("return ...", &(1,));
+    pipeline.eval("return ...", &UnencodableArgs);
+    let mut results = pipeline.send(&Options::default()).wait_all();
+    assert_eq!(results.len(), 2);
+
+    let err = results.pop().unwrap().map(drop).unwrap_err();
+    assert!(err.to_string().contains("can't encode"), "{}", err);
+    let result: (u32,) = results
+        .pop()
+        .unwrap()
+        .unwrap()
+        .unwrap()
+        .into_struct()
+        .unwrap();
+    assert_eq!(result, (1,));
+}
+
 pub fn test_insert() {
     let mut local_space = Space::find("test_s1").unwrap();
     local_space.truncate().unwrap();
@@ -253,6 +821,78 @@ pub fn test_insert() {
     assert_eq!(output.unwrap().into_struct::<S1Record>().unwrap(), input);
 }
 
+pub fn test_typed_ops() {
+    let mut local_space = Space::find("test_s1").unwrap();
+    local_space.truncate().unwrap();
+
+    let conn = Conn::new(
+        "localhost:3301",
+        ConnOptions {
+            user: "test_user".to_string(),
+            password: "password".to_string(),
+            ..ConnOptions::default()
+        },
+        None,
+    )
+    .unwrap();
+    let mut remote_space = conn.space("test_s1").unwrap().unwrap();
+
+    let input = S1Record {
+        id: 1,
+        text: "Test".to_string(),
+    };
+    let inserted = remote_space
+        .insert_typed(&input, &Options::default())
+        .unwrap();
+    assert_eq!(inserted, Some(input.clone()));
+
+    let replaced = remote_space
+        .replace_typed(
+            &S1Record {
+                id: 2,
+                text: "Test 2".to_string(),
+            },
+            &Options::default(),
+        )
+        .unwrap();
+    assert_eq!(replaced.map(|r| r.id), Some(2));
+
+    let selected: Vec<S1Record> = remote_space
+        .select_typed(IteratorType::All, &(), &Options::default())
+        .unwrap();
+    assert_eq!(
+        selected,
+        vec![
+            input.clone(),
+            S1Record {
+                id: 2,
+                text: "Test 2".to_string()
+            }
+        ]
+    );
+
+    let updated: Option<S1Record> = remote_space
+        .update_typed(
+            &(1,),
+            &vec![QueryOperation {
+                op: "=".to_string(),
+                field_id: 1,
+                value: "Updated".into(),
+            }],
+            &Options::default(),
+        )
+        .unwrap();
+    assert_eq!(updated.unwrap().text, "Updated");
+
+    let deleted: Option<S1Record> = remote_space
+        .delete_typed(&(2,), &Options::default())
+        .unwrap();
+    assert_eq!(deleted.map(|r| r.id), Some(2));
+
+    let missing: Option<S1Record> = remote_space.get_typed(&(2,), &Options::default()).unwrap();
+    assert!(missing.is_none());
+}
+
 pub fn test_replace() {
     let mut local_space = Space::find("test_s1").unwrap();
     local_space.truncate().unwrap();
@@ -453,8 +1093,8 @@ pub fn test_cancel_recv() {
         for _ in 0..10 {
             match conn.ping(&Options::default()) {
                 Ok(_) => {}
-                Err(Error::IO(e)) if e.kind() == io::ErrorKind::ConnectionAborted => {}
-                Err(Error::IO(e)) if e.kind() == io::ErrorKind::NotConnected => {}
+                Err(e) if e.io_kind() == Some(io::ErrorKind::ConnectionAborted) => {}
+                Err(e) if e.io_kind() == Some(io::ErrorKind::NotConnected) => {}
                 e => e.unwrap(),
             }
         }
@@ -463,7 +1103,7 @@ pub fn test_cancel_recv() {
     fiber.set_joinable(true);
     fiber.start(conn.clone());
     conn.close();
-    fiber.join();
+    fiber.join().unwrap();
 }
 
 pub fn test_triggers_connect() {
@@ -0,0 +1,38 @@
+use std::cell::Cell;
+
+use tarantool::fiber::{sleep, Fiber, Once};
+
+pub fn test_once_concurrent() {
+    let once = Once::new();
+    let runs = Cell::new(0);
+    let initialized = Cell::new(0);
+
+    let mut worker = |_| {
+        once.call_once(|| {
+            // other fibers call `call_once` while the initializer yields
+            sleep(0.01);
+            runs.set(runs.get() + 1);
+        });
+        assert_eq!(runs.get(), 1);
+        initialized.set(initialized.get() + 1);
+        0
+    };
+
+    let mut fibers = vec![];
+    for _ in 0..5 {
+        let mut fiber = Fiber::new("test_fiber", &mut worker);
+        fiber.set_joinable(true);
+        fiber.start(());
+        fibers.push(fiber);
+    }
+    for fiber in fibers {
+        fiber.join().unwrap();
+    }
+
+    assert_eq!(runs.get(), 1);
+    assert_eq!(initialized.get(), 5);
+    assert!(once.is_completed());
+
+    once.call_once(|| runs.set(runs.get() + 1));
+    assert_eq!(runs.get(), 1);
+}
@@ -0,0 +1,52 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use tarantool::fiber::{sleep, Fiber, Semaphore};
+
+pub fn test_semaphore_concurrency() {
+    let semaphore = Semaphore::new(3);
+    let active = Cell::new(0);
+    let max_active = Cell::new(0);
+    let completed = Cell::new(0);
+
+    let mut worker = |_| {
+        let _permit = semaphore.acquire();
+        active.set(active.get() + 1);
+        max_active.set(max_active.get().max(active.get()));
+        sleep(0.01);
+        active.set(active.get() - 1);
+        completed.set(completed.get() + 1);
+        0
+    };
+
+    let mut fibers = vec![];
+    for _ in 0..10 {
+        let mut fiber = Fiber::new("test_fiber", &mut worker);
+        fiber.set_joinable(true);
+        fiber.start(());
+        fibers.push(fiber);
+    }
+    for fiber in fibers {
+        fiber.join().unwrap();
+    }
+
+    assert_eq!(completed.get(), 10);
+    assert_eq!(max_active.get(), 3);
+    assert_eq!(semaphore.available_permits(), 3);
+}
+
+pub fn test_semaphore_try_acquire() {
+    let semaphore = Semaphore::new(1);
+
+    let permit = semaphore.try_acquire().unwrap();
+    assert!(semaphore.try_acquire().is_none());
+    assert!(semaphore
+        .acquire_timeout(Duration::from_millis(10))
+        .is_none());
+
+    drop(permit);
+    assert_eq!(semaphore.available_permits(), 1);
+    assert!(semaphore
+        .acquire_timeout(Duration::from_millis(10))
+        .is_some());
+}
@@ -0,0 +1,66 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use tarantool::fiber::{clock, sleep, Fiber, ShutdownBarrier};
+
+pub fn test_shutdown_barrier() {
+    let barrier = ShutdownBarrier::new(3);
+    let iterations = Cell::new(0);
+    let exited = Cell::new(0);
+
+    // a busy worker polls the barrier between the work items
+    let mut busy_worker = |_| {
+        while !barrier.should_stop() {
+            iterations.set(iterations.get() + 1);
+            sleep(0.001);
+        }
+        exited.set(exited.get() + 1);
+        barrier.worker_done();
+        0
+    };
+    // an idle worker is woken up by the shutdown
+    let mut idle_worker = |_| {
+        while !barrier.wait_stop(Duration::from_secs(10)) {}
+        exited.set(exited.get() + 1);
+        barrier.worker_done();
+        0
+    };
+
+    let mut fibers = vec![];
+    for i in 0..3 {
+        let mut fiber = if i == 0 {
+            Fiber::new("test_fiber", &mut idle_worker)
+        } else {
+            Fiber::new("test_fiber", &mut busy_worker)
+        };
+        fiber.set_joinable(true);
+        fiber.start(());
+        fibers.push(fiber);
+    }
+
+    sleep(0.01);
+    assert!(!barrier.should_stop());
+    assert_eq!(barrier.remaining(), 3);
+    assert!(iterations.get() > 0);
+
+    let start = clock();
+    assert!(barrier.shutdown(Duration::from_secs(1)));
+    assert!(clock() - start < 0.1);
+    assert_eq!(exited.get(), 3);
+    assert_eq!(barrier.remaining(), 0);
+
+    for fiber in fibers {
+        fiber.join().unwrap();
+    }
+}
+
+pub fn test_shutdown_barrier_timeout() {
+    // the worker never acknowledges the shutdown
+    let barrier = ShutdownBarrier::new(1);
+    assert!(!barrier.shutdown(Duration::from_millis(10)));
+    assert!(barrier.should_stop());
+    assert_eq!(barrier.remaining(), 1);
+
+    barrier.worker_done();
+    assert!(barrier.shutdown(Duration::from_millis(10)));
+}
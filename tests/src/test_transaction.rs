@@ -1,6 +1,7 @@
 use std::io;
 
-use tarantool::error::Error;
+use tarantool::error::{Error, TransactionError};
+use tarantool::index::IteratorType;
 use tarantool::space::Space;
 use tarantool::transaction::start_transaction;
 
@@ -42,3 +43,45 @@ pub fn test_transaction_rollback() {
     let output = space.get(&(1,)).unwrap();
     assert!(output.is_none());
 }
+
+pub fn test_transaction_iterator_ended() {
+    let mut space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+    for i in 0..3 {
+        space
+            .insert(&S1Record {
+                id: i,
+                text: "test".to_string(),
+            })
+            .unwrap();
+    }
+
+    let mut iter = start_transaction(|| -> Result<_, Error> {
+        let mut iter = space.select(IteratorType::All, &())?;
+        assert!(iter.try_next()?.is_some());
+        Ok(iter)
+    })
+    .unwrap();
+    assert!(matches!(
+        iter.try_next(),
+        Err(Error::Transaction(TransactionError::Ended))
+    ));
+    assert!(iter.next().is_none());
+
+    let mut stream = start_transaction(|| -> Result<_, Error> {
+        let mut stream = space.select_stream::<S1Record, _>(IteratorType::All, &())?;
+        assert!(stream.next().unwrap().is_ok());
+        Ok(stream)
+    })
+    .unwrap();
+    assert!(matches!(
+        stream.next(),
+        Some(Err(Error::Transaction(TransactionError::Ended)))
+    ));
+    assert!(stream.next().is_none());
+
+    // iterators opened outside of a transaction are not affected
+    let iter = space.select(IteratorType::All, &()).unwrap();
+    start_transaction(|| -> Result<_, Error> { Ok(()) }).unwrap();
+    assert_eq!(iter.count(), 3);
+}
@@ -1,8 +1,16 @@
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 
-use tarantool::tuple::{FieldType, KeyDef, KeyDefItem, Tuple};
+use serde::{Deserialize, Serialize};
 
-use crate::common::{S1Record, S2Key, S2Record};
+use tarantool::error::Error;
+use tarantool::ffi::tarantool::BoxTuple;
+use tarantool::space::Space;
+use tarantool::tuple::{
+    AsTuple, FieldType, FunctionArgs, KeyDef, KeyDefItem, OldValue, Tuple, TupleFormat,
+};
+
+use crate::common::{QueryOperation, S1Record, S2Key, S2Record};
 
 pub fn test_tuple_new_from_struct() {
     let input = S1Record {
@@ -12,6 +20,54 @@ pub fn test_tuple_new_from_struct() {
     assert!(Tuple::from_struct(&input).is_ok());
 }
 
+pub fn test_tuple_new_not_an_array() {
+    #[derive(Serialize)]
+    struct Attrs(BTreeMap<String, u32>);
+    impl AsTuple for Attrs {}
+
+    #[derive(Serialize)]
+    struct Id(u32);
+    impl AsTuple for Id {}
+
+    let mut attrs = BTreeMap::new();
+    attrs.insert("id".to_string(), 1);
+    let result = Tuple::from_struct(&Attrs(attrs));
+    assert!(matches!(result, Err(Error::NotAnArray)));
+
+    let result = Tuple::from_struct(&Id(1));
+    assert!(matches!(result, Err(Error::NotAnArray)));
+}
+
+pub fn test_tuple_deserialize() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Record<'a> {
+        #[serde(rename = "record_id")]
+        id: u32,
+        text: &'a str,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Pair(u32, String);
+
+    let tuple = Tuple::from_struct(&(1, "text")).unwrap();
+    let record = Record::deserialize(&tuple).unwrap();
+    assert_eq!(
+        record,
+        Record {
+            id: 1,
+            text: "text"
+        }
+    );
+    // the string is borrowed from the tuple
+    let raw = tuple.as_raw_bytes().as_ptr_range();
+    assert!(raw.contains(&record.text.as_ptr()));
+
+    let pair = Pair::deserialize(&tuple).unwrap();
+    assert_eq!(pair, Pair(1, "text".to_string()));
+
+    assert!(<(String, u32)>::deserialize(&tuple).is_err());
+}
+
 pub fn test_tuple_field_count() {
     // struct -> tuple
     let tuple = Tuple::from_struct(&S2Record {
@@ -45,6 +101,18 @@ pub fn test_tuple_size() {
     assert_eq!(tuple.bsize(), 14);
 }
 
+pub fn test_tuple_byte_size() {
+    let tuple = Tuple::from_struct(&(1, "key", vec![1, 2, 3])).unwrap();
+    assert_eq!(tuple.byte_size(), tuple.as_raw_bytes().len());
+    assert_eq!(tuple.byte_size(), tuple.bsize());
+    assert!(!tuple.is_empty());
+
+    let empty = Tuple::from_struct(&Vec::<()>::new()).unwrap();
+    assert_eq!(empty.len(), 0);
+    assert!(empty.is_empty());
+    assert_eq!(empty.byte_size(), 1);
+}
+
 pub fn test_tuple_into_struct() {
     let input = S2Record {
         id: 1,
@@ -71,6 +139,66 @@ pub fn test_tuple_into_struct() {
     );
 }
 
+pub fn test_tuple_into_struct_field_error() {
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Record {
+        id: u32,
+        key: String,
+        a: i32,
+    }
+
+    let tuple = Tuple::from_struct(&(1, "key", "value")).unwrap();
+    match tuple.into_struct::<Record>() {
+        Err(Error::DecodeField(e)) => {
+            assert_eq!(e.fieldno(), 3);
+            assert_eq!(e.name(), Some("a"));
+            assert!(e.to_string().starts_with("field 3 (a): "));
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+}
+
+pub fn test_tuple_to_json() {
+    let mut map = BTreeMap::new();
+    map.insert("b".to_string(), vec![1.5]);
+    map.insert("a".to_string(), vec![]);
+    let tuple = Tuple::from_struct(&(42, -7, "text", map)).unwrap();
+    assert_eq!(tuple.to_json(), r#"[42,-7,"text",{"a":[],"b":[1.5]}]"#);
+
+    // [bin, decimal -12.34, uuid]
+    let mut data = vec![0x93, 0xc4, 0x03, 0x01, 0x02, 0x03];
+    data.extend(&[0xd6, 0x01, 0x02, 0x01, 0x23, 0x4d]);
+    data.extend(&[0xd8, 0x02]);
+    data.extend((0..16u8).map(|i| i * 0x11));
+    let tuple: Tuple = FunctionArgs {
+        args: data.as_ptr() as _,
+        args_end: unsafe { data.as_ptr().add(data.len()) } as _,
+    }
+    .into();
+    assert_eq!(
+        tuple.to_json(),
+        r#"["AQID","-12.34","00112233-4455-6677-8899-aabbccddeeff"]"#
+    );
+}
+
+pub fn test_tuple_serialize_ext() {
+    // [decimal -12.34, uuid]
+    let mut data = vec![0x92, 0xd6, 0x01, 0x02, 0x01, 0x23, 0x4d];
+    data.extend(&[0xd8, 0x02]);
+    data.extend((0..16u8).map(|i| i * 0x11));
+    let tuple: Tuple = FunctionArgs {
+        args: data.as_ptr() as _,
+        args_end: unsafe { data.as_ptr().add(data.len()) } as _,
+    }
+    .into();
+
+    let nested = Tuple::from_struct(&(1, &tuple)).unwrap();
+    let mut expected = vec![0x92, 0x01];
+    expected.extend(&data);
+    assert_eq!(nested.as_raw_bytes(), &expected[..]);
+}
+
 pub fn test_tuple_clone() {
     let tuple_1 = {
         let tuple_2 = Tuple::from_struct(&S1Record {
@@ -83,6 +211,64 @@ pub fn test_tuple_clone() {
     assert!(tuple_1.into_struct::<S1Record>().is_ok());
 }
 
+/// Reads the reference counter of a tuple.
+///
+/// Relies on `struct tuple` layout: the (small) reference counter is stored in the first byte.
+fn tuple_refs(ptr: *mut BoxTuple) -> u8 {
+    unsafe { *(ptr as *const u8) }
+}
+
+pub fn test_tuple_into_raw_from_raw() {
+    let tuple = Tuple::from_struct(&S1Record {
+        id: 1,
+        text: "text".to_string(),
+    })
+    .unwrap();
+    let copy = tuple.clone();
+    let ptr = copy.into_raw();
+    assert_eq!(tuple_refs(ptr), 2);
+
+    let restored = unsafe { Tuple::from_raw(ptr) };
+    assert_eq!(tuple_refs(ptr), 2);
+    assert_eq!(restored.into_struct::<S1Record>().unwrap().id, 1);
+    assert_eq!(tuple_refs(ptr), 1);
+}
+
+pub fn test_tuple_as_raw_bytes() {
+    let tuple = Tuple::from_struct(&S1Record {
+        id: 1,
+        text: "text".to_string(),
+    })
+    .unwrap();
+    assert_eq!(tuple.as_raw_bytes(), b"\x92\x01\xa4text");
+
+    let tuple = Tuple::from_struct(&vec![0u8; 20]).unwrap();
+    let mut expected = vec![0xdc, 0x00, 0x14];
+    expected.extend_from_slice(&[0; 20]);
+    assert_eq!(tuple.as_raw_bytes(), &expected[..]);
+
+    let tuple = Tuple::from_struct(&Vec::<u32>::new()).unwrap();
+    assert_eq!(tuple.as_raw_bytes(), b"\x90");
+}
+
+pub fn test_tuple_raw_fields() {
+    let tuple = Tuple::from_struct(&(1, "text", vec![1, 2, 3], ("nested", -100000, 3.5))).unwrap();
+    let fields: Vec<&[u8]> = tuple.raw_fields().collect();
+    assert_eq!(fields.len(), 4);
+    assert_eq!(fields[0], b"\x01");
+    assert_eq!(fields[1], b"\xa4text");
+    assert_eq!(fields[2], b"\x93\x01\x02\x03");
+
+    let mut reconstructed = vec![0x94];
+    for field in &fields {
+        reconstructed.extend_from_slice(field);
+    }
+    assert_eq!(reconstructed, tuple.as_raw_bytes());
+
+    let tuple = Tuple::from_struct(&Vec::<u32>::new()).unwrap();
+    assert_eq!(tuple.raw_fields().count(), 0);
+}
+
 pub fn test_tuple_iterator() {
     let tuple = Tuple::from_struct(&S1Record {
         id: 1,
@@ -125,6 +311,83 @@ pub fn test_tuple_get_format() {
     let _ = tuple.format();
 }
 
+pub fn test_tuple_new_with_format() {
+    let mut space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+    let input = S1Record {
+        id: 1,
+        text: "text".to_string(),
+    };
+    let space_tuple = space.insert(&input).unwrap().unwrap();
+
+    // the format of a space tuple is the space format
+    let format = space_tuple.format();
+    assert!(format.clone() == format);
+    assert!(format != TupleFormat::default());
+    drop(space_tuple);
+
+    let tuple = Tuple::new_with_format(&(2, "other text"), &format).unwrap();
+    assert!(tuple.format() == format);
+    assert_eq!(
+        tuple.into_struct::<S1Record>().unwrap(),
+        S1Record {
+            id: 2,
+            text: "other text".to_string()
+        }
+    );
+
+    // the format validates field types
+    assert!(Tuple::new_with_format(&("not an id", "text"), &format).is_err());
+    assert!(Tuple::new_with_format(&("not an id", "text"), &TupleFormat::default()).is_ok());
+}
+
+pub fn test_tuple_apply_ops_returning() {
+    let tuple = Tuple::from_struct(&(1, "a", 10)).unwrap();
+    let ops = vec![
+        QueryOperation {
+            op: "=".to_string(),
+            field_id: 2,
+            value: "b".into(),
+        },
+        QueryOperation {
+            op: "!".to_string(),
+            field_id: 4,
+            value: "x".into(),
+        },
+        QueryOperation {
+            op: "+".to_string(),
+            field_id: 3,
+            value: 5.into(),
+        },
+    ];
+    let (new_tuple, old_values) = tuple.apply_ops_returning(&ops).unwrap();
+
+    assert_eq!(
+        new_tuple
+            .into_struct::<(u32, String, u32, String)>()
+            .unwrap(),
+        (1, "b".to_string(), 15, "x".to_string())
+    );
+    assert_eq!(old_values.len(), 2);
+    assert_eq!(old_values[0].op_index, 0);
+    assert_eq!(old_values[0].fieldno, 1);
+    assert_eq!(old_values[0].decode::<String>().unwrap(), "a");
+    assert_eq!(
+        old_values[1],
+        OldValue {
+            op_index: 2,
+            fieldno: 2,
+            data: vec![10],
+        }
+    );
+
+    // the original tuple is not changed
+    assert_eq!(
+        tuple.into_struct::<(u32, String, u32)>().unwrap(),
+        (1, "a".to_string(), 10)
+    );
+}
+
 pub fn test_tuple_get_field() {
     let tuple = Tuple::from_struct(&S2Record {
         id: 1,